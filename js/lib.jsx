@@ -27,15 +27,18 @@ CategorySelector.propTypes = {
   onChange: PropTypes.func.isRequired,
 };
 
-function RankingResult({ score, place, drop }) {
+function RankingResult({
+  score, place, drop, compensation,
+}) {
+  const value = compensation ? <em title="Compensatiepunten organisatie">{score}</em> : score;
   return (
     <div className="col">
-      {!drop && score}
-      {drop && <del>{score}</del>}
+      {!drop && value}
+      {drop && <del>{value}</del>}
       <br />
       <span className="text-muted">
         (
-        {place}
+        {compensation ? 'org.' : place}
         )
       </span>
     </div>
@@ -46,12 +49,14 @@ RankingResult.propTypes = {
   score: PropTypes.number,
   place: PropTypes.oneOfType([PropTypes.number, PropTypes.string]),
   drop: PropTypes.bool,
+  compensation: PropTypes.bool,
 };
 
 RankingResult.defaultProps = {
   score: 0,
   place: '-',
   drop: false,
+  compensation: false,
 };
 
 function RankingEntry({ entry }) {
@@ -80,6 +85,7 @@ function RankingEntry({ entry }) {
                 score={result.score || undefined}
                 place={result.place || undefined}
                 drop={result.drop || false}
+                compensation={result.compensation || false}
               />
             ),
           )}
@@ -103,12 +109,24 @@ RankingEntry.propTypes = {
       score: PropTypes.number,
       place: PropTypes.number,
       drop: PropTypes.bool,
+      compensation: PropTypes.bool,
     })),
   }).isRequired,
 };
 
+function rankingUrl(dataDirectory, cup, season, category) {
+  if (dataDirectory) {
+    return new URL(`./${dataDirectory}/${cup}/${season}/${encodeURIComponent(category)}.json`, window.location);
+  }
+  const url = new URL('./cgi-bin/cup-cgi', window.location);
+  url.searchParams.set('cup', cup);
+  url.searchParams.set('season', season);
+  url.searchParams.set('ageClass', category);
+  return url;
+}
+
 function Ranking({
-  categories, cup, season, events,
+  categories, cup, season, events, dataDirectory,
 }) {
   const [selectedCategory, setSelectedCategory] = useState('');
   const [isLoading, setLoading] = useState(false);
@@ -151,11 +169,10 @@ function Ranking({
       return;
     }
     setLoading(true);
-    const url = new URL('./cgi-bin/cup-cgi', window.location);
-    url.searchParams.set('cup', cup);
-    url.searchParams.set('season', season);
-    url.searchParams.set('ageClass', selectedCategory);
-    url.searchParams.set('events', events);
+    const url = rankingUrl(dataDirectory, cup, season, selectedCategory);
+    if (!dataDirectory) {
+      url.searchParams.set('events', events);
+    }
 
     fetch(url)
       .then((response) => response.json())
@@ -197,6 +214,11 @@ Ranking.propTypes = {
   cup: PropTypes.string.isRequired,
   season: PropTypes.string.isRequired,
   events: PropTypes.number.isRequired,
+  dataDirectory: PropTypes.string,
+};
+
+Ranking.defaultProps = {
+  dataDirectory: undefined,
 };
 
 const categories = [];
@@ -204,16 +226,38 @@ document.querySelectorAll('#ranking span').forEach((category) => {
   categories.push(category.textContent);
 });
 
+function fetchCategories(cup, season, dataDirectory) {
+  if (categories.length > 0) {
+    return Promise.resolve(categories);
+  }
+  if (dataDirectory) {
+    const url = new URL(`./${dataDirectory}/${cup}/${season}/index.json`, window.location);
+    return fetch(url)
+      .then((response) => response.json())
+      .then((index) => index.categories);
+  }
+  const url = new URL('./cgi-bin/cup-cgi', window.location);
+  url.searchParams.set('type', 'categories');
+  url.searchParams.set('cup', cup);
+  url.searchParams.set('season', season);
+  return fetch(url).then((response) => response.json());
+}
+
 const rankingContainer = document.getElementById('ranking');
 if (rankingContainer) {
-  const root = createRoot(rankingContainer);
-  root.render(
-    <Ranking
-      categories={categories}
-      cup={rankingContainer.dataset.cup}
-      season={rankingContainer.dataset.season}
-      events={parseFloat(rankingContainer.dataset.events)}
-    />,
-    rankingContainer,
-  );
+  const { cup, season } = rankingContainer.dataset;
+  const dataDirectory = rankingContainer.dataset.static;
+  fetchCategories(cup, season, dataDirectory).then((seasonCategories) => {
+    const root = createRoot(rankingContainer);
+    root.render(
+      <Ranking
+        categories={seasonCategories}
+        cup={cup}
+        season={season}
+        events={parseFloat(rankingContainer.dataset.events)}
+        dataDirectory={dataDirectory}
+      />,
+      rankingContainer,
+    );
+  });
 }
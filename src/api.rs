@@ -0,0 +1,253 @@
+// Ranking API shared by the CGI program and the server
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::NaiveTime;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    clubs::{calculate_club_ranking, ClubGrouping},
+    db::Database,
+    diff::compare_rules,
+    discovery::{list_categories, list_cups, list_events, list_seasons},
+    explain::explain_ranking,
+    export::{export_rankings, ExportFormat},
+    history::runner_history,
+    progression::ranking_progression,
+    snapshots::official_ranking_with,
+    whatif::{what_if_ranking, HypotheticalResult, WhatIf},
+    RankingOptions,
+};
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: &impl Serialize) -> Self {
+        Response {
+            status: 200,
+            content_type: "application/json",
+            body: serde_json::to_vec(value).unwrap(),
+        }
+    }
+
+    fn text(status: u16, body: String) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into_bytes(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("missing parameter '{0}'")]
+    MissingParameter(&'static str),
+    #[error("invalid parameter '{0}'")]
+    InvalidParameter(&'static str),
+    #[error("parameter '{0}' should be a number")]
+    NotANumber(&'static str),
+    #[error("unknown type '{0}'")]
+    UnknownType(String),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<ApiError> for Response {
+    fn from(err: ApiError) -> Self {
+        let status = match err {
+            ApiError::Internal(_) => 500,
+            _ => 400,
+        };
+        Response::text(status, err.to_string())
+    }
+}
+
+/// Answer a query string of the ranking API.
+pub fn handle_query(db: &dyn Database, query: &str) -> Response {
+    let params: HashMap<_, _> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+
+    handle(db, &params).unwrap_or_else(Response::from)
+}
+
+fn handle(db: &dyn Database, params: &HashMap<String, String>) -> Result<Response, ApiError> {
+    match params.get("type").map_or("ranking", |t| t.as_str()) {
+        "ranking" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            let events_count = number(params, "events")?;
+            let options = ranking_options(params)?;
+            if let Some(format) = params.get("format").filter(|format| *format != "json") {
+                let format = ExportFormat::from_str(format)
+                    .map_err(|_| ApiError::InvalidParameter("format"))?;
+                // All categories of the season unless one is asked for
+                let age_classes = match params.get("ageClass") {
+                    Some(age_class) => vec![age_class.to_owned()],
+                    None => list_categories(db, &cup, season)?,
+                };
+                let export = export_rankings(
+                    db,
+                    &cup,
+                    season,
+                    &age_classes,
+                    events_count,
+                    &options,
+                    format,
+                )?;
+                return Ok(Response {
+                    status: 200,
+                    content_type: format.content_type(),
+                    body: export,
+                });
+            }
+            let age_class = required(params, "ageClass")?;
+            let ranking =
+                official_ranking_with(db, cup, season, age_class, events_count, &options)?;
+            Ok(Response::json(&ranking))
+        }
+        "explain" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            let age_class = required(params, "ageClass")?;
+            let events_count = number(params, "events")?;
+            let options = ranking_options(params)?;
+            let explanation =
+                explain_ranking(db, &cup, season, &age_class, events_count, &options)?;
+            Ok(Response::json(&explanation))
+        }
+        "compare" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            // All categories of the season unless one is asked for
+            let age_classes: Vec<String> = params.get("ageClass").cloned().into_iter().collect();
+            let options = ranking_options(params)?;
+            let diffs = compare_rules(db, &cup, season, &age_classes, &options)?;
+            Ok(Response::json(&diffs))
+        }
+        "progression" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            let age_class = required(params, "ageClass")?;
+            let events_count = number(params, "events")?;
+            let progression = ranking_progression(db, &cup, season, &age_class, events_count)?;
+            Ok(Response::json(&progression))
+        }
+        "whatif" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            let age_class = required(params, "ageClass")?;
+            let events_count = number(params, "events")?;
+            let event_id = match params.get("event") {
+                Some(event_id) => Some(
+                    event_id
+                        .parse::<i64>()
+                        .map_err(|_| ApiError::NotANumber("event"))?,
+                ),
+                None => None,
+            };
+            let result = match (params.get("score"), params.get("time")) {
+                (Some(_), None) => HypotheticalResult::Score(
+                    u32::try_from(number(params, "score")?)
+                        .map_err(|_| ApiError::InvalidParameter("score"))?,
+                ),
+                (None, Some(time)) => HypotheticalResult::Time(
+                    NaiveTime::from_str(time).map_err(|_| ApiError::InvalidParameter("time"))?,
+                ),
+                (None, None) => return Err(ApiError::MissingParameter("score")),
+                (Some(_), Some(_)) => return Err(ApiError::InvalidParameter("time")),
+            };
+            let what_if = WhatIf {
+                name: required(params, "name")?,
+                event_id,
+                result,
+            };
+            let ranking = what_if_ranking(db, &cup, season, &age_class, events_count, &what_if)?;
+            Ok(Response::json(&ranking))
+        }
+        "club" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            let grouping = match params.get("grouping") {
+                Some(grouping) => ClubGrouping::from_str(grouping)
+                    .map_err(|_| ApiError::InvalidParameter("grouping"))?,
+                None => ClubGrouping::Category,
+            };
+            let scores_count = match params.get("scores") {
+                Some(_) => number(params, "scores")?,
+                None => 3,
+            };
+            let ranking = calculate_club_ranking(db, cup, season, grouping, scores_count)?;
+            Ok(Response::json(&ranking))
+        }
+        "runner" => {
+            let name = required(params, "name")?;
+            let history = runner_history(db, &name)?;
+            Ok(Response::json(&history))
+        }
+        "cups" => Ok(Response::json(&list_cups(db)?)),
+        "seasons" => {
+            let cup = required(params, "cup")?;
+            Ok(Response::json(&list_seasons(db, &cup)?))
+        }
+        "events" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            Ok(Response::json(&list_events(db, &cup, season)?))
+        }
+        "categories" => {
+            let cup = required(params, "cup")?;
+            let season = season(params)?;
+            Ok(Response::json(&list_categories(db, &cup, season)?))
+        }
+        other => Err(ApiError::UnknownType(other.to_owned())),
+    }
+}
+
+fn required(params: &HashMap<String, String>, name: &'static str) -> Result<String, ApiError> {
+    params
+        .get(name)
+        .map(|value| value.to_string())
+        .ok_or(ApiError::MissingParameter(name))
+}
+
+fn season(params: &HashMap<String, String>) -> Result<i16, ApiError> {
+    required(params, "season")?
+        .parse::<i16>()
+        .map_err(|_| ApiError::InvalidParameter("season"))
+}
+
+/// The moment, rules and policies to calculate a ranking with.
+fn ranking_options(params: &HashMap<String, String>) -> Result<RankingOptions, ApiError> {
+    Ok(RankingOptions {
+        as_of: optional(params, "asOf")?,
+        rules: optional(params, "rules")?,
+        wrong_course: optional(params, "wrongCourse")?,
+        reference: optional(params, "reference")?,
+        reference_courses: optional(params, "referenceCourse")?.map(|course| vec![course]),
+    })
+}
+
+fn optional<T: FromStr>(
+    params: &HashMap<String, String>,
+    name: &'static str,
+) -> Result<Option<T>, ApiError> {
+    params
+        .get(name)
+        .map(|value| T::from_str(value).map_err(|_| ApiError::InvalidParameter(name)))
+        .transpose()
+}
+
+fn number(params: &HashMap<String, String>, name: &'static str) -> Result<usize, ApiError> {
+    required(params, name)?
+        .parse::<usize>()
+        .map_err(|_| ApiError::NotANumber(name))
+}
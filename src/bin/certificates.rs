@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::certificates::{render_certificates, render_combined};
+use ov_cup::db::LocalDatabase;
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Only these categories, all categories of the season by default
+    #[arg(long)]
+    age_class: Vec<String>,
+
+    /// Number of places per category that get a certificate
+    #[arg(long, default_value = "3")]
+    places: u32,
+
+    #[arg(long, default_value = "templates")]
+    templates: PathBuf,
+
+    /// Directory with the cup logos
+    #[arg(long, default_value = "images")]
+    images: PathBuf,
+
+    /// Directory for one SVG file per runner
+    #[arg(long, default_value = "certificates")]
+    output: PathBuf,
+
+    /// Write a single printable HTML page instead
+    #[arg(long)]
+    combined: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let certificates = render_certificates(
+        &db,
+        &args.templates,
+        &args.images,
+        &args.cup,
+        args.season,
+        &args.age_class,
+        args.places,
+    )?;
+
+    match args.combined {
+        Some(path) => {
+            let title = format!("{} {}", args.cup, args.season);
+            fs::write(
+                &path,
+                render_combined(&args.templates, &title, &certificates)?,
+            )?;
+            eprintln!(
+                "Rendered {} certificates to {}",
+                certificates.len(),
+                path.display()
+            );
+        }
+        None => {
+            fs::create_dir_all(&args.output)?;
+            for certificate in &certificates {
+                fs::write(args.output.join(&certificate.file_name), &certificate.svg)?;
+            }
+            eprintln!(
+                "Rendered {} certificates to {}",
+                certificates.len(),
+                args.output.display()
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::clinch::{format_outlook, season_outlook};
+use ov_cup::db::LocalDatabase;
+
+use ov_cup::cli;
+
+/// Best and worst possible final place of every runner
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Only list these categories, all categories of the season by default
+    #[arg(long)]
+    age_class: Vec<String>,
+
+    /// Number of events left in the season
+    #[arg(long)]
+    remaining_events: usize,
+
+    /// Output as json instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let outlooks = season_outlook(
+        &db,
+        &args.cup,
+        args.season,
+        &args.age_class,
+        args.remaining_events,
+    )?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outlooks)?);
+    } else {
+        print!("{}", format_outlook(&outlooks));
+    }
+    Ok(())
+}
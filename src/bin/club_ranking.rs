@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::clubs::{calculate_club_ranking, export_club_ranking, ClubGrouping};
+use ov_cup::db::LocalDatabase;
+use ov_cup::export::ExportFormat;
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_cup)]
+    cup: String,
+
+    #[arg(long, default_value = "2020")]
+    season: i16,
+
+    /// Group runners by category, gender or overall
+    #[arg(long, default_value = "category")]
+    grouping: ClubGrouping,
+
+    /// Number of scores that count per group in each event
+    #[arg(long, default_value = "3")]
+    scores_count: usize,
+
+    /// Output as text, json, markdown, csv, tsv or xlsx
+    #[arg(long, default_value = "text")]
+    format: ExportFormat,
+
+    /// Write the output to a file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let ranking =
+        calculate_club_ranking(&db, args.cup, args.season, args.grouping, args.scores_count)?;
+    let export = export_club_ranking(&db, &ranking, args.format)?;
+    match args.output {
+        Some(path) => std::fs::write(path, export)?,
+        None => std::io::stdout().write_all(&export)?,
+    }
+    Ok(())
+}
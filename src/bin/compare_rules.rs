@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Parser};
+use itertools::Itertools;
+use ov_cup::db::LocalDatabase;
+use ov_cup::diff::{compare_rules, format_diff};
+use ov_cup::seasons::{
+    reference_courses, reference_policy, wrong_course_policy, ReferenceCourse, ReferencePolicy,
+    WrongCoursePolicy,
+};
+use ov_cup::{rule_version, RankingOptions, RuleVersion};
+
+use ov_cup::cli;
+
+/// Show how the rankings of a season change under other rules or policies
+#[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("proposal").required(true).multiple(true).args(["rules", "wrong_course", "reference", "reference_course"])))]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Only compare these categories, all categories of the season by default
+    #[arg(long)]
+    age_class: Vec<String>,
+
+    /// Rules to compare with: 2022, 2023, 2024 or 2026
+    #[arg(long)]
+    rules: Option<RuleVersion>,
+
+    /// Wrong course policy to compare with: exclude, zero, penalty:<percent> or allow
+    #[arg(long)]
+    wrong_course: Option<WrongCoursePolicy>,
+
+    /// Reference policy to compare with: all, eligible, eligible-finishers or gender
+    #[arg(long)]
+    reference: Option<ReferencePolicy>,
+
+    /// Reference courses to compare with: <class>:<course>=<reference course>
+    #[arg(long)]
+    reference_course: Vec<ReferenceCourse>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+
+    let mut proposal = vec![];
+    if let Some(rules) = args.rules {
+        proposal.push(format!(
+            "rules {} -> {}",
+            rule_version(&args.cup, args.season),
+            rules
+        ));
+    }
+    if let Some(wrong_course) = args.wrong_course {
+        proposal.push(format!(
+            "wrong course {} -> {}",
+            wrong_course_policy(&args.cup, args.season),
+            wrong_course
+        ));
+    }
+    if let Some(reference) = args.reference {
+        proposal.push(format!(
+            "reference {} -> {}",
+            reference_policy(&args.cup, args.season),
+            reference
+        ));
+    }
+    if !args.reference_course.is_empty() {
+        proposal.push(format!(
+            "reference courses {} -> {}",
+            reference_courses(&args.cup, args.season).iter().join(" "),
+            args.reference_course.iter().join(" ")
+        ));
+    }
+    eprintln!("{} {}: {}", args.cup, args.season, proposal.join(", "));
+
+    let options = RankingOptions {
+        rules: args.rules,
+        wrong_course: args.wrong_course,
+        reference: args.reference,
+        reference_courses: (!args.reference_course.is_empty()).then_some(args.reference_course),
+        ..Default::default()
+    };
+    let diffs = compare_rules(&db, &args.cup, args.season, &args.age_class, &options)?;
+    if diffs.is_empty() {
+        eprintln!("No changes");
+    }
+    print!("{}", format_diff(&diffs));
+    Ok(())
+}
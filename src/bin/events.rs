@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+use ov_cup::db::LocalDatabase;
+use ov_cup::events::{
+    delete_event, event_details, list_stored_events, move_event, update_event, EventUpdate,
+};
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the stored events
+    List {
+        #[arg(long, value_parser = cli::parse_cup)]
+        cup: Option<String>,
+
+        #[arg(long)]
+        season: Option<i16>,
+    },
+    /// Show an event with its results per age class and its organisers
+    Show {
+        #[arg(long)]
+        event: i64,
+    },
+    /// Rename an event or change its location or date
+    Edit {
+        #[arg(long)]
+        event: i64,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long)]
+        location: Option<String>,
+
+        /// New day of the event, e.g. 2026-01-17
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// Move an event to another cup or season
+    Move {
+        #[arg(long)]
+        event: i64,
+
+        #[arg(long, value_parser = cli::parse_cup)]
+        cup: String,
+
+        #[arg(long)]
+        season: i16,
+    },
+    /// Delete an event with its results, courses and organisers
+    Delete {
+        #[arg(long)]
+        event: i64,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    ov_cup::create_database(&db)?;
+
+    match args.command {
+        Command::List { cup, season } => {
+            for event in list_stored_events(&db, cup.as_deref(), season)? {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{} results",
+                    event.id,
+                    event.cup,
+                    event.season,
+                    event.date.date_naive(),
+                    event.name,
+                    event.location,
+                    event.results
+                );
+            }
+        }
+        Command::Show { event } => {
+            let details = event_details(&db, event)?;
+            let event = details.event;
+            println!("{} {}", event.id, event.name);
+            println!("Cup:      {} {}", event.cup, event.season);
+            println!("Date:     {}", event.date);
+            println!("Location: {}", event.location);
+            println!("Results:  {}", event.results);
+            for age_class in details.age_classes {
+                println!("  {}\t{}", age_class.age_class, age_class.results);
+            }
+            if !details.organisers.is_empty() {
+                println!("Organisers:");
+                for organiser in details.organisers {
+                    println!(
+                        "  {}\t{}\t{}",
+                        organiser.name, organiser.club, organiser.role
+                    );
+                }
+            }
+        }
+        Command::Edit {
+            event,
+            name,
+            location,
+            date,
+        } => update_event(
+            &db,
+            event,
+            EventUpdate {
+                name,
+                location,
+                date,
+            },
+        )?,
+        Command::Move { event, cup, season } => move_event(&db, event, &cup, season)?,
+        Command::Delete { event } => {
+            let deleted = delete_event(&db, event)?;
+            eprintln!(
+                "Deleted event {} with {} results, {} courses and {} organisers, removed {} runners",
+                event, deleted.results, deleted.courses, deleted.organisers, deleted.runners
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::db::LocalDatabase;
+use ov_cup::history::runner_history;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long)]
+    name: String,
+
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let history = runner_history(&db, &args.name)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&history)?);
+        return Ok(());
+    }
+
+    println!("{} ({})", history.name, history.club);
+    for result in &history.results {
+        println!(
+            "{}\t{}\t{} {}\t{}\t{}\t{}\t{}\t{}\t{}",
+            &result.date[..10.min(result.date.len())],
+            result.event,
+            result.cup,
+            result.season,
+            result.course,
+            result.age_class,
+            result.position,
+            result.time,
+            result
+                .score
+                .map_or("-".to_owned(), |score| score.to_string()),
+            result.location,
+        );
+    }
+    println!();
+    for standing in &history.standings {
+        println!(
+            "{} {}\t{}\t{}\t{}",
+            standing.cup,
+            standing.season,
+            standing.age_class,
+            standing
+                .place
+                .map_or("-".to_owned(), |place| format!("{}.", place)),
+            standing.error.as_deref().map_or_else(
+                || standing
+                    .total_score
+                    .map_or("-".to_owned(), |score| score.to_string()),
+                |error| format!("error: {}", error)
+            ),
+        );
+    }
+    Ok(())
+}
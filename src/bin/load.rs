@@ -7,8 +7,11 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use clap::Parser;
+use ov_cup::class_changes::{store_class_changes, ClassChange};
 use ov_cup::db::LocalDatabase;
+use ov_cup::diff::{diff_seasons, format_diff, season_rankings};
 use ov_cup::iof;
+use ov_cup::registry::{store_registry, Registration};
 use ov_cup::AgeClassOverride;
 use ov_cup::Competitor;
 
@@ -32,8 +35,21 @@ struct Args {
     #[arg(long, default_value = "overrides.json")]
     overrides: String,
 
+    /// Class history of runners changing age class during the season, replaces the stored history
+    #[arg(long)]
+    class_changes: Option<String>,
+
+    /// Federation, nationality and licence of runners, to decide who is ranked, replaces the
+    /// stored registry
+    #[arg(long)]
+    registry: Option<String>,
+
     #[arg(long)]
     competitor_list: Vec<String>,
+
+    /// Don't print how the rankings changed
+    #[arg(long)]
+    quiet: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -47,6 +63,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             age_class_override.cup == cup && age_class_override.season == season
         })
         .collect();
+    let class_changes: Option<Vec<ClassChange>> = match args.class_changes {
+        Some(path) => Some(
+            read_class_changes_json(path)?
+                .into_iter()
+                .filter(|class_change| class_change.cup == cup && class_change.season == season)
+                .collect(),
+        ),
+        None => None,
+    };
+    let registry = match args.registry {
+        Some(path) => Some(read_registry_json(path)?),
+        None => None,
+    };
     let competitors = read_competitor_lists(&args.competitor_list)?;
     let options = ov_cup::ResultProcessingOptions {
         cup,
@@ -60,11 +89,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db = LocalDatabase::new(db_path);
     ov_cup::create_database(&db)?;
 
+    let season: i16 = args.season.parse()?;
+    let before = season_rankings(&db, &args.cup, season)?;
+
+    // Only files that are passed replace what is stored
+    if let Some(class_changes) = class_changes {
+        store_class_changes(&db, &args.cup, &args.season, &class_changes)?;
+    }
+    if let Some(registry) = registry {
+        store_registry(&db, &registry)?;
+    }
+
     for path in args.paths {
         let event = webres::read_event_json(path)?;
         ov_cup::store_event(&db, event, &options)?;
     }
 
+    if !args.quiet {
+        let after = season_rankings(&db, &args.cup, season)?;
+        print!("{}", format_diff(&diff_seasons(&before, &after)));
+    }
+
     Ok(())
 }
 
@@ -75,6 +120,20 @@ fn read_overrides_json(path: String) -> Result<Vec<AgeClassOverride>, Box<dyn st
     Ok(overrides)
 }
 
+fn read_class_changes_json(path: String) -> Result<Vec<ClassChange>, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let class_changes = serde_json::from_reader(reader)?;
+    Ok(class_changes)
+}
+
+fn read_registry_json(path: String) -> Result<Vec<Registration>, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let registry = serde_json::from_reader(reader)?;
+    Ok(registry)
+}
+
 fn read_competitor_lists(paths: &[String]) -> anyhow::Result<Vec<Competitor>> {
     let mut competitors = vec![];
     for path in paths {
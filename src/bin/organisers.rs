@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use ov_cup::db::LocalDatabase;
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Register a course builder or volunteer of an event
+    Add {
+        #[arg(long)]
+        event: i64,
+
+        #[arg(long)]
+        name: String,
+
+        #[arg(long, default_value = "")]
+        club: String,
+
+        #[arg(long, default_value = "organiser")]
+        role: String,
+    },
+    /// Remove a registered organiser from an event
+    Remove {
+        #[arg(long)]
+        event: i64,
+
+        #[arg(long)]
+        name: String,
+    },
+    /// List the organisers of all events in a season
+    List {
+        #[arg(long, default_value = "forest-cup", value_parser = cli::parse_cup)]
+        cup: String,
+
+        #[arg(long)]
+        season: i16,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    ov_cup::create_database(&db)?;
+
+    match args.command {
+        Command::Add {
+            event,
+            name,
+            club,
+            role,
+        } => ov_cup::store_organiser(&db, event, &name, &club, &role)?,
+        Command::Remove { event, name } => ov_cup::remove_organiser(&db, event, &name)?,
+        Command::List { cup, season } => {
+            for organiser in ov_cup::list_organisers(&db, &cup, season)? {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    organiser.event_id, organiser.name, organiser.club, organiser.role
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::db::LocalDatabase;
+use ov_cup::pages::{read_page_metadata, render_season_page};
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Event images and links, banners and notices per season
+    #[arg(long, default_value = "pages.json")]
+    metadata: PathBuf,
+
+    #[arg(long, default_value = "templates")]
+    templates: PathBuf,
+
+    /// Directory with the HTML pages
+    #[arg(long, default_value = ".")]
+    output: PathBuf,
+
+    /// Read the rankings from the JSON files `publish` wrote to this directory, relative to the page
+    #[arg(long)]
+    static_data: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let metadata = read_page_metadata(&args.metadata)?;
+    let path = render_season_page(
+        &db,
+        &args.templates,
+        &args.output,
+        &metadata,
+        &args.cup,
+        args.season,
+        args.static_data.as_deref(),
+    )?;
+    eprintln!("Rendered {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::db::LocalDatabase;
+use ov_cup::podium::{format_podium, podium, PodiumFormat};
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Only list these categories, all categories of the season by default
+    #[arg(long)]
+    age_class: Vec<String>,
+
+    /// Number of places per category
+    #[arg(long, default_value = "3")]
+    places: u32,
+
+    /// Output as csv, markdown or json
+    #[arg(long, default_value = "csv")]
+    format: PodiumFormat,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let podiums = podium(&db, &args.cup, args.season, &args.age_class, args.places)?;
+    print!("{}", format_podium(&podiums, args.format)?);
+    Ok(())
+}
@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::db::LocalDatabase;
+use ov_cup::print::{ranking_sheets, render_html, render_pdf};
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    #[arg(long, default_value = "templates")]
+    templates: PathBuf,
+
+    /// A PDF file when the name ends in .pdf, an HTML page otherwise
+    #[arg(long)]
+    output: PathBuf,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let sheets = ranking_sheets(&db, &args.cup, args.season)?;
+    if args
+        .output
+        .extension()
+        .is_some_and(|extension| extension == "pdf")
+    {
+        std::fs::write(&args.output, render_pdf(&sheets)?)?;
+    } else {
+        std::fs::write(&args.output, render_html(&args.templates, &sheets)?)?;
+    }
+    eprintln!("Rendered {}", args.output.display());
+    Ok(())
+}
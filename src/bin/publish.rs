@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use ov_cup::db::LocalDatabase;
+use ov_cup::publish::publish;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// Directory that receives the ranking JSON files
+    #[arg(long, default_value = "data")]
+    output: PathBuf,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    let published = publish(&db, &args.output)?;
+    eprintln!(
+        "Published {} rankings to {}",
+        published,
+        args.output.display()
+    );
+    Ok(())
+}
@@ -1,17 +1,27 @@
 // SPDX-FileCopyrightText: 2021 Jeroen Hoekx
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::io::Write;
 use std::path::PathBuf;
 
 use clap::Parser;
+use itertools::Itertools;
+use ov_cup::combined::find_combined_cup;
 use ov_cup::db::LocalDatabase;
+use ov_cup::discovery::list_categories;
+use ov_cup::explain::{explain_ranking, format_explanation};
+use ov_cup::export::{export_rankings, ExportFormat};
 
-use ov_cup::calculate_ranking;
 use ov_cup::cli;
+use ov_cup::seasons::{
+    reference_courses, reference_policy, wrong_course_policy, ReferenceCourse, ReferencePolicy,
+    WrongCoursePolicy,
+};
+use ov_cup::{rule_version, AsOf, RankingOptions, RuleVersion};
 
 #[derive(Parser, Debug)]
 struct Args {
-    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_cup)]
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
     cup: String,
 
     #[arg(long, default_value = "2020")]
@@ -20,20 +30,136 @@ struct Args {
     #[arg(long, default_value = "H35")]
     age_class: String,
 
+    /// Rank every category of the season instead of --age-class
+    #[arg(long)]
+    all_categories: bool,
+
     #[arg(long, default_value = "4")]
     events_count: usize,
+
+    /// Rank the season as it stood after an event id or on a date (YYYY-MM-DD)
+    #[arg(long)]
+    as_of: Option<AsOf>,
+
+    /// Output as text, json, csv, tsv, markdown or xlsx
+    #[arg(long, default_value = "text")]
+    format: ExportFormat,
+
+    /// Write the output to a file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Rank with these rules (2022, 2023, 2024 or 2026) instead of the rules of the season
+    #[arg(long)]
+    rules: Option<RuleVersion>,
+
+    /// Rank results in a shorter course with this policy (exclude, zero, penalty:<percent> or
+    /// allow) instead of the policy of the season
+    #[arg(long)]
+    wrong_course: Option<WrongCoursePolicy>,
+
+    /// Take the reference time of a course from these runners (all, eligible,
+    /// eligible-finishers or gender) instead of the policy of the season
+    #[arg(long)]
+    reference: Option<ReferencePolicy>,
+
+    /// Score a course of a category against another course too (<class>:<course>=<reference
+    /// course>), instead of the reference courses of the season
+    #[arg(long)]
+    reference_course: Vec<ReferenceCourse>,
+
+    /// Explain how every score was calculated, as text or json
+    #[arg(long)]
+    explain: bool,
+
+    /// Print the version of the rules and the policies the ranking is calculated with
+    #[arg(long)]
+    show_rules: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
     let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
-    let ranking = calculate_ranking(
-        &db,
-        args.cup,
-        args.season,
-        args.age_class,
-        args.events_count,
-    )?;
-    dbg!(ranking);
+
+    if args.show_rules {
+        let cups: Vec<&str> = match find_combined_cup(&args.cup, args.season) {
+            Some(combined_cup) => combined_cup.cups.iter().map(|cup| cup.cup).collect(),
+            None => vec![&args.cup],
+        };
+        for cup in cups {
+            let reference_courses = if args.reference_course.is_empty() {
+                reference_courses(cup, args.season)
+            } else {
+                &args.reference_course
+            };
+            eprintln!(
+                "{} {}: rules {}, wrong course {}, reference {}, reference courses {}",
+                cup,
+                args.season,
+                args.rules.unwrap_or_else(|| rule_version(cup, args.season)),
+                args.wrong_course
+                    .unwrap_or_else(|| wrong_course_policy(cup, args.season)),
+                args.reference
+                    .unwrap_or_else(|| reference_policy(cup, args.season)),
+                if reference_courses.is_empty() {
+                    "none".to_owned()
+                } else {
+                    reference_courses.iter().join(" ")
+                }
+            );
+        }
+    }
+
+    let age_classes = if args.all_categories {
+        list_categories(&db, &args.cup, args.season)?
+    } else {
+        vec![args.age_class]
+    };
+    let options = RankingOptions {
+        as_of: args.as_of,
+        rules: args.rules,
+        wrong_course: args.wrong_course,
+        reference: args.reference,
+        reference_courses: (!args.reference_course.is_empty()).then_some(args.reference_course),
+    };
+    let export = if args.explain {
+        if !matches!(args.format, ExportFormat::Text | ExportFormat::Json) {
+            return Err("An explanation is only printed as text or json".into());
+        }
+        let mut explanations = vec![];
+        for age_class in &age_classes {
+            explanations.push(explain_ranking(
+                &db,
+                &args.cup,
+                args.season,
+                age_class,
+                args.events_count,
+                &options,
+            )?);
+        }
+        match args.format {
+            ExportFormat::Json => serde_json::to_vec_pretty(&explanations)?,
+            _ => explanations
+                .iter()
+                .map(format_explanation)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .into_bytes(),
+        }
+    } else {
+        export_rankings(
+            &db,
+            &args.cup,
+            args.season,
+            &age_classes,
+            args.events_count,
+            &options,
+            args.format,
+        )?
+    };
+    match args.output {
+        Some(path) => std::fs::write(path, export)?,
+        None => std::io::stdout().write_all(&export)?,
+    }
     Ok(())
 }
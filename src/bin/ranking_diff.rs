@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use ov_cup::diff::{diff_seasons, format_diff, SeasonRankings};
+use ov_cup::RankingEntry;
+use serde::Deserialize;
+
+/// Compare rankings saved with `ranking --format json`
+#[derive(Parser, Debug)]
+struct Args {
+    before: PathBuf,
+
+    after: PathBuf,
+}
+
+// Rankings of several categories, or a single ranking as returned by the API
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedRankings {
+    Categories(SeasonRankings),
+    Ranking(Vec<RankingEntry>),
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let before = read_rankings(&args.before)?;
+    let after = read_rankings(&args.after)?;
+    print!("{}", format_diff(&diff_seasons(&before, &after)));
+    Ok(())
+}
+
+fn read_rankings(path: &Path) -> anyhow::Result<SeasonRankings> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(match serde_json::from_reader(reader)? {
+        SavedRankings::Categories(rankings) => rankings,
+        SavedRankings::Ranking(ranking) => SeasonRankings::from([(String::new(), ranking)]),
+    })
+}
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use ov_cup::api;
+use ov_cup::db::{Database, PooledDatabase};
+use percent_encoding::percent_decode_str;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    listen: String,
+
+    /// Directory with the HTML pages, dist/ and images/
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+
+    #[arg(long, default_value = "ov.sqlite")]
+    database: PathBuf,
+
+    #[arg(long, default_value = "4")]
+    threads: usize,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = Arc::new(PooledDatabase::read_only(
+        args.database,
+        args.threads as u32,
+    )?);
+    let server = Arc::new(Server::http(&args.listen).map_err(|err| err.to_string())?);
+    let root = Arc::new(args.root);
+
+    let running = Arc::new(AtomicBool::new(true));
+    {
+        let running = running.clone();
+        ctrlc::set_handler(move || running.store(false, Ordering::SeqCst))?;
+    }
+
+    eprintln!("Listening on http://{}", args.listen);
+    let workers: Vec<_> = (0..args.threads)
+        .map(|_| {
+            let db = db.clone();
+            let server = server.clone();
+            let root = root.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                // Finish the request in progress before shutting down
+                while running.load(Ordering::SeqCst) {
+                    match server.recv_timeout(Duration::from_millis(500)) {
+                        Ok(Some(request)) => handle(request, db.as_ref(), &root),
+                        Ok(None) => continue,
+                        Err(err) => eprintln!("Failed to receive request: {}", err),
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    eprintln!("Stopped");
+    Ok(())
+}
+
+fn handle(request: Request, db: &dyn Database, root: &Path) {
+    if request.method() != &Method::Get && request.method() != &Method::Head {
+        let _ = request.respond(Response::from_string("method not allowed").with_status_code(405));
+        return;
+    }
+
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let result = match path {
+        "/health" => health(request, db),
+        "/cgi-bin/cup-cgi" | "/api" => {
+            let response = api::handle_query(db, query);
+            request.respond(
+                Response::from_data(response.body)
+                    .with_status_code(response.status)
+                    .with_header(content_type(response.content_type)),
+            )
+        }
+        _ => match static_file(root, path) {
+            Some((data, mime_type)) => {
+                request.respond(Response::from_data(data).with_header(content_type(mime_type)))
+            }
+            None => request.respond(Response::from_string("not found").with_status_code(404)),
+        },
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to send response for {}: {}", url, err);
+    }
+}
+
+fn health(request: Request, db: &dyn Database) -> std::io::Result<()> {
+    let healthy = db.open().and_then(|conn| {
+        Ok(conn.query_row("select count(*) from Event", [], |row| row.get::<_, i64>(0))?)
+    });
+    match healthy {
+        Ok(_) => request.respond(Response::from_string("ok")),
+        Err(err) => request.respond(Response::from_string(err.to_string()).with_status_code(503)),
+    }
+}
+
+// Serve the HTML pages and the dist/ and images/ directories, nothing else
+fn static_file(root: &Path, path: &str) -> Option<(Vec<u8>, &'static str)> {
+    // Decode the path like the web server does for the CGI, encoded dots can't escape the root
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    let path = if path == "/" { "/index.html" } else { &path };
+    let relative = Path::new(path.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    let mime_type = mime_type(relative)?;
+    let allowed = match components.next() {
+        None => mime_type == "text/html; charset=utf-8" || first == "favicon.ico",
+        Some(_) => first == "dist" || first == "images",
+    };
+    if !allowed {
+        return None;
+    }
+
+    fs::read(root.join(relative))
+        .ok()
+        .map(|data| (data, mime_type))
+}
+
+fn mime_type(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "html" => Some("text/html; charset=utf-8"),
+        "js" => Some("text/javascript"),
+        "css" => Some("text/css"),
+        "json" => Some("application/json"),
+        "svg" => Some("image/svg+xml"),
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "ico" => Some("image/x-icon"),
+        "woff" => Some("font/woff"),
+        "woff2" => Some("font/woff2"),
+        "map" => Some("application/json"),
+        _ => None,
+    }
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).unwrap()
+}
@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use ov_cup::db::LocalDatabase;
+use ov_cup::diff::{format_diff, CategoryDiff};
+use ov_cup::discovery::list_categories;
+use ov_cup::seasons;
+use ov_cup::snapshots::{check_snapshot, freeze_ranking, list_snapshots, remove_snapshot};
+
+use ov_cup::cli;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(ClapArgs, Debug)]
+struct Season {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    /// Only these categories, all categories of the season by default
+    #[arg(long)]
+    age_class: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Freeze the rankings of a season when they are declared final
+    Freeze {
+        #[command(flatten)]
+        season: Season,
+
+        /// Replace rankings that are already frozen
+        #[arg(long)]
+        replace: bool,
+    },
+    /// Report the differences between the frozen rankings and a fresh calculation,
+    /// all frozen categories by default
+    Check {
+        #[command(flatten)]
+        season: Season,
+    },
+    /// List the frozen rankings of a season
+    List {
+        #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+        cup: String,
+
+        #[arg(long)]
+        season: i16,
+    },
+    /// Remove frozen rankings, they are calculated live again,
+    /// all frozen categories by default
+    Remove {
+        #[command(flatten)]
+        season: Season,
+    },
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+    ov_cup::create_database(&db)?;
+
+    match args.command {
+        Command::Freeze { season, replace } => {
+            let events_count = seasons::events_count(&season.cup, season.season);
+            for age_class in age_classes(&db, &season)? {
+                freeze_ranking(
+                    &db,
+                    &season.cup,
+                    season.season,
+                    &age_class,
+                    events_count,
+                    replace,
+                )?;
+                eprintln!("Froze {} {} {}", season.cup, season.season, age_class);
+            }
+        }
+        Command::Check { season } => {
+            let events_count = seasons::events_count(&season.cup, season.season);
+            let mut diffs = vec![];
+            for age_class in frozen_age_classes(&db, &season)? {
+                let changes =
+                    check_snapshot(&db, &season.cup, season.season, &age_class, events_count)?;
+                if !changes.is_empty() {
+                    diffs.push(CategoryDiff { age_class, changes });
+                }
+            }
+            print!("{}", format_diff(&diffs));
+            if !diffs.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::List { cup, season } => {
+            for snapshot in list_snapshots(&db, &cup, season)? {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    snapshot.cup,
+                    snapshot.season,
+                    snapshot.age_class,
+                    snapshot.events_count,
+                    snapshot.created
+                );
+            }
+        }
+        Command::Remove { season } => {
+            for age_class in frozen_age_classes(&db, &season)? {
+                remove_snapshot(&db, &season.cup, season.season, &age_class)?;
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn age_classes(db: &LocalDatabase, season: &Season) -> anyhow::Result<Vec<String>> {
+    if season.age_class.is_empty() {
+        list_categories(db, &season.cup, season.season)
+    } else {
+        Ok(season.age_class.clone())
+    }
+}
+
+fn frozen_age_classes(db: &LocalDatabase, season: &Season) -> anyhow::Result<Vec<String>> {
+    if season.age_class.is_empty() {
+        Ok(list_snapshots(db, &season.cup, season.season)?
+            .into_iter()
+            .map(|snapshot| snapshot.age_class)
+            .collect())
+    } else {
+        Ok(season.age_class.clone())
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use chrono::NaiveTime;
+use clap::{ArgGroup, Parser};
+use ov_cup::db::LocalDatabase;
+use ov_cup::whatif::{what_if_ranking, HypotheticalResult, WhatIf};
+
+use ov_cup::cli;
+
+/// Rank a category with a hypothetical result, the database is not changed
+#[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("result").required(true).args(["score", "time"])))]
+struct Args {
+    #[arg(long, default_value = "forest-cup", value_parser = cli::parse_ranking_cup)]
+    cup: String,
+
+    #[arg(long)]
+    season: i16,
+
+    #[arg(long)]
+    age_class: String,
+
+    #[arg(long, default_value = "4")]
+    events_count: usize,
+
+    /// Runner with the hypothetical result
+    #[arg(long)]
+    name: String,
+
+    /// Event of the season, the next event by default
+    #[arg(long)]
+    event: Option<i64>,
+
+    /// Points in the event
+    #[arg(long)]
+    score: Option<u32>,
+
+    /// Running time (HH:MM:SS), scored against the other results of the course
+    #[arg(long)]
+    time: Option<NaiveTime>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let db = LocalDatabase::new(PathBuf::from("ov.sqlite"));
+
+    let result = match (args.score, args.time) {
+        (Some(score), _) => HypotheticalResult::Score(score),
+        (_, Some(time)) => HypotheticalResult::Time(time),
+        _ => unreachable!("clap requires a score or a time"),
+    };
+    let what_if = WhatIf {
+        name: args.name,
+        event_id: args.event,
+        result,
+    };
+    let ranking = what_if_ranking(
+        &db,
+        &args.cup,
+        args.season,
+        &args.age_class,
+        args.events_count,
+        &what_if,
+    )?;
+    println!("{}", serde_json::to_string_pretty(&ranking)?);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// Certificates for the podium places
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use minijinja::{context, path_loader, AutoEscape, Environment};
+
+use crate::{db::Database, pages::cup_title, podium::podium};
+
+/// A rendered certificate and the file name to store it under.
+#[derive(Debug)]
+pub struct Certificate {
+    pub file_name: String,
+    pub svg: String,
+}
+
+/// Fill `certificate.svg` for every runner on the first `places` places of each category.
+///
+/// The logo of the cup in `images/<cup>.svg` is embedded, so the certificates can be opened anywhere.
+pub fn render_certificates(
+    db: &dyn Database,
+    templates: &Path,
+    images: &Path,
+    cup: &str,
+    season: i16,
+    age_classes: &[String],
+    places: u32,
+) -> anyhow::Result<Vec<Certificate>> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(templates));
+    env.set_auto_escape_callback(|_| AutoEscape::Html);
+    let template = env.get_template("certificate.svg")?;
+
+    let logo = match fs::read(images.join(format!("{}.svg", cup))) {
+        Ok(logo) => Some(format!(
+            "data:image/svg+xml;base64,{}",
+            STANDARD.encode(logo)
+        )),
+        Err(_) => None,
+    };
+
+    let mut certificates = vec![];
+    for category in podium(db, cup, season, age_classes, places)? {
+        for place in category.places {
+            let svg = template.render(context! {
+                logo,
+                cup_title => cup_title(cup),
+                season,
+                age_class => category.age_class,
+                place => place.place,
+                ordinal => dutch_ordinal(place.place),
+                name => place.name,
+                club => place.club,
+                total_score => place.total_score,
+            })?;
+            certificates.push(Certificate {
+                file_name: format!(
+                    "{}-{}-{}-{}-{}.svg",
+                    cup,
+                    season,
+                    slug(&category.age_class),
+                    place.place,
+                    slug(&place.name)
+                ),
+                svg,
+            });
+        }
+    }
+    Ok(certificates)
+}
+
+/// Put all certificates in one page with a certificate per printed page.
+pub fn render_combined(
+    templates: &Path,
+    title: &str,
+    certificates: &[Certificate],
+) -> anyhow::Result<String> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(templates));
+    let template = env.get_template("certificates.html")?;
+    let certificates: Vec<&str> = certificates
+        .iter()
+        .map(|certificate| certificate.svg.as_str())
+        .collect();
+    Ok(template.render(context! { title, certificates })?)
+}
+
+// 1ste, 2de, 3de, 8ste, 20ste
+fn dutch_ordinal(place: u32) -> String {
+    if place == 1 || place == 8 || place >= 20 {
+        format!("{}ste", place)
+    } else {
+        format!("{}de", place)
+    }
+}
+
+fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dutch_ordinal, slug};
+
+    #[test]
+    fn ordinals() {
+        assert_eq!(dutch_ordinal(1), "1ste");
+        assert_eq!(dutch_ordinal(2), "2de");
+        assert_eq!(dutch_ordinal(3), "3de");
+        assert_eq!(dutch_ordinal(8), "8ste");
+        assert_eq!(dutch_ordinal(20), "20ste");
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(slug("Jan Van Der Berg"), "jan-van-der-berg");
+        assert_eq!(slug("H. Open"), "h-open");
+        assert_eq!(slug("D-10"), "d-10");
+    }
+}
@@ -0,0 +1,384 @@
+// Runners changing age class during a season
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use itertools::Itertools;
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+
+use crate::{db::Database, Performance, COURSES_COLORS, COURSES_NUMBERED};
+
+/// A step in the class history of a runner.
+///
+/// Results before `date` count as they were scored on the course of `from_class`,
+/// a history can't score them against another course.
+#[derive(Debug, Deserialize)]
+pub struct ClassChange {
+    pub cup: String,
+    pub season: String,
+    pub name: String,
+    #[serde(rename = "fromclass")]
+    pub from_class: String,
+    #[serde(rename = "ageclass")]
+    pub age_class: String,
+    /// First day the runner runs in the new class.
+    pub date: NaiveDate,
+}
+
+/// Store the class history of a season, replacing the stored history.
+pub fn store_class_changes(
+    db: &dyn Database,
+    cup: &str,
+    season: &str,
+    changes: &[ClassChange],
+) -> anyhow::Result<()> {
+    validate_class_changes(season.parse()?, changes)?;
+
+    let conn = db.open()?;
+    conn.execute(
+        "delete from ClassChange where cup = ? and season = ?",
+        params![cup, season],
+    )?;
+    for change in changes {
+        conn.execute(
+            "
+            insert into Runner (name, club) values (?, '')
+            on conflict (name) do nothing
+        ",
+            params![change.name],
+        )?;
+        conn.execute(
+            "
+            insert into ClassChange (cup, season, runner_id, from_class, age_class, date)
+            select ?, ?, id, ?, ?, ? from Runner where name = ?
+        ",
+            params![
+                cup,
+                season,
+                change.from_class,
+                change.age_class,
+                change.date,
+                change.name
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// The age class has a course in the season.
+fn has_course(season: i16, age_class: &str) -> bool {
+    if season >= 2025 {
+        COURSES_COLORS.contains_key(age_class)
+    } else {
+        COURSES_NUMBERED.contains_key(age_class)
+    }
+}
+
+/// Every class has a course in the season and the steps of a runner follow each other.
+fn validate_class_changes(season: i16, changes: &[ClassChange]) -> anyhow::Result<()> {
+    for change in changes {
+        for age_class in [&change.from_class, &change.age_class] {
+            if !has_course(season, age_class) {
+                bail!(
+                    "{}: age class {} has no course in {}",
+                    change.name,
+                    age_class,
+                    season
+                );
+            }
+        }
+        if change.from_class == change.age_class
+            || change.from_class.chars().next() != change.age_class.chars().next()
+        {
+            bail!(
+                "{}: can't change from {} to {}",
+                change.name,
+                change.from_class,
+                change.age_class
+            );
+        }
+        if change.date.year() != season as i32 {
+            bail!(
+                "{}: change to {} on {} is not in {}",
+                change.name,
+                change.age_class,
+                change.date,
+                season
+            );
+        }
+    }
+
+    for (name, steps) in &changes
+        .iter()
+        .sorted_by_key(|change| (&change.name, change.date))
+        .chunk_by(|change| &change.name)
+    {
+        let steps: Vec<&ClassChange> = steps.collect();
+        for (previous, next) in steps.iter().tuple_windows() {
+            if previous.date == next.date || previous.age_class != next.from_class {
+                bail!(
+                    "{}: change from {} on {} does not follow the change to {} on {}",
+                    name,
+                    next.from_class,
+                    next.date,
+                    previous.age_class,
+                    previous.date
+                );
+            }
+        }
+        // Results in a class would count twice when the runner returns to it
+        let mut classes: HashSet<&str> = steps
+            .iter()
+            .map(|change| change.from_class.as_str())
+            .take(1)
+            .collect();
+        for change in &steps {
+            if !classes.insert(&change.age_class) {
+                bail!(
+                    "{}: can't return to {} on {}",
+                    name,
+                    change.age_class,
+                    change.date
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A class a runner ran in before changing to the ranked class.
+#[derive(Debug, PartialEq, Eq)]
+struct EarlierClass {
+    age_class: String,
+    /// First day in the next class.
+    until: NaiveDate,
+}
+
+/// Class histories of a season that decide which earlier results count for a class.
+#[derive(Debug)]
+pub(crate) struct ClassChanges {
+    /// Runners with a class history.
+    runners: HashSet<String>,
+    /// Earlier classes of the runners that changed to the ranked class.
+    earlier_classes: HashMap<String, Vec<EarlierClass>>,
+    event_dates: HashMap<i64, NaiveDate>,
+}
+
+impl ClassChanges {
+    /// Class changes up to the given moment.
+    pub(crate) fn find(
+        conn: &Connection,
+        cup: &str,
+        season: i16,
+        age_class: &str,
+        until: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Self> {
+        let mut stmt = conn.prepare(
+            "
+            select Runner.name, ClassChange.from_class, ClassChange.age_class, ClassChange.date
+            from ClassChange join Runner on ClassChange.runner_id = Runner.id
+            where ClassChange.cup = ? and ClassChange.season = ?
+        ",
+        )?;
+        let steps: Vec<(String, String, String, NaiveDate)> = stmt
+            .query_map(params![cup, season], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|(_, _, _, date)| until.is_none_or(|until| *date <= until.date_naive()))
+            .collect();
+
+        let mut stmt = conn.prepare("select id, date from Event where cup = ? and season = ?")?;
+        let event_dates = stmt
+            .query_map(params![cup, season], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, DateTime<Utc>>(1)?.date_naive(),
+                ))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(ClassChanges {
+            runners: steps.iter().map(|(name, ..)| name.clone()).collect(),
+            earlier_classes: earlier_classes(&steps, age_class),
+            event_dates,
+        })
+    }
+
+    pub(crate) fn has_runner(&self, name: &str) -> bool {
+        self.runners.contains(name)
+    }
+
+    /// The runner changed to the ranked class.
+    pub(crate) fn changed_to(&self, name: &str) -> bool {
+        self.earlier_classes.contains_key(name)
+    }
+
+    /// Classes whose results may count for the ranked class.
+    pub(crate) fn earlier_age_classes(&self) -> Vec<String> {
+        self.earlier_classes
+            .values()
+            .flatten()
+            .map(|earlier_class| earlier_class.age_class.clone())
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// The result was run in an earlier class of the runner, before they left it.
+    pub(crate) fn carries_over(&self, performance: &Performance) -> bool {
+        let Some(date) = self.event_dates.get(&performance.event_id) else {
+            return false;
+        };
+        self.earlier_classes
+            .get(&performance.name)
+            .into_iter()
+            .flatten()
+            .any(|earlier_class| {
+                earlier_class.age_class == performance.age_class && *date < earlier_class.until
+            })
+    }
+}
+
+// Follow the steps back from the change to the ranked class
+fn earlier_classes(
+    steps: &[(String, String, String, NaiveDate)],
+    age_class: &str,
+) -> HashMap<String, Vec<EarlierClass>> {
+    let mut earlier_classes = HashMap::new();
+    for (name, from_class, _, date) in steps.iter().filter(|(_, _, to, _)| to == age_class) {
+        let mut classes = vec![EarlierClass {
+            age_class: from_class.clone(),
+            until: *date,
+        }];
+        // Each step back is earlier, so this ends
+        while let Some((_, from_class, _, date)) = steps.iter().find(|(other, _, to, date)| {
+            let last = classes.last().unwrap();
+            other == name && *to == last.age_class && *date < last.until
+        }) {
+            classes.push(EarlierClass {
+                age_class: from_class.clone(),
+                until: *date,
+            });
+        }
+        earlier_classes.insert(name.clone(), classes);
+    }
+    earlier_classes
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{earlier_classes, validate_class_changes, ClassChange, EarlierClass};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn change(name: &str, from_class: &str, age_class: &str, date: NaiveDate) -> ClassChange {
+        ClassChange {
+            cup: "forest-cup".to_owned(),
+            season: "2026".to_owned(),
+            name: name.to_owned(),
+            from_class: from_class.to_owned(),
+            age_class: age_class.to_owned(),
+            date,
+        }
+    }
+
+    #[test]
+    fn valid_history() {
+        let changes = vec![
+            change("A", "H-18", "H-20", date(6, 1)),
+            change("A", "H-16", "H-18", date(3, 1)),
+            change("B", "D35", "D40", date(2, 1)),
+        ];
+        assert!(validate_class_changes(2026, &changes).is_ok());
+    }
+
+    #[test]
+    fn invalid_history() {
+        // Classes without a course
+        assert!(
+            validate_class_changes(2026, &[change("A", "H-16", "H:Zeart", date(3, 1))]).is_err()
+        );
+        // Change of gender
+        assert!(validate_class_changes(2026, &[change("A", "H-16", "D-16", date(3, 1))]).is_err());
+        // Outside the season
+        assert!(validate_class_changes(
+            2026,
+            &[change(
+                "A",
+                "H-16",
+                "H-18",
+                NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+            )]
+        )
+        .is_err());
+        // Steps that don't follow each other
+        assert!(validate_class_changes(
+            2026,
+            &[
+                change("A", "H-16", "H-18", date(3, 1)),
+                change("A", "H-16", "H-20", date(6, 1)),
+            ]
+        )
+        .is_err());
+        // Returning to an earlier class
+        assert!(validate_class_changes(
+            2026,
+            &[
+                change("A", "H-16", "H-18", date(3, 1)),
+                change("A", "H-18", "H-16", date(6, 1)),
+            ]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn earlier_classes_over_several_steps() {
+        let steps = vec![
+            (
+                "A".to_owned(),
+                "H-16".to_owned(),
+                "H-18".to_owned(),
+                date(3, 1),
+            ),
+            (
+                "A".to_owned(),
+                "H-18".to_owned(),
+                "H-20".to_owned(),
+                date(6, 1),
+            ),
+            (
+                "B".to_owned(),
+                "H-18".to_owned(),
+                "H-20".to_owned(),
+                date(4, 1),
+            ),
+        ];
+        let classes = earlier_classes(&steps, "H-20");
+        assert_eq!(
+            classes["A"],
+            vec![
+                EarlierClass {
+                    age_class: "H-18".to_owned(),
+                    until: date(6, 1)
+                },
+                EarlierClass {
+                    age_class: "H-16".to_owned(),
+                    until: date(3, 1)
+                },
+            ]
+        );
+        assert_eq!(classes["B"].len(), 1);
+        assert_eq!(earlier_classes(&steps, "H-18")["A"].len(), 1);
+        assert!(earlier_classes(&steps, "H21").is_empty());
+    }
+}
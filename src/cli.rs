@@ -16,3 +16,15 @@ pub fn parse_cup(flag: &str) -> Result<String, ArgumentsError> {
         Err(ArgumentsError::UnknownCup)
     }
 }
+
+/// Parse a cup that can be ranked, including combined rankings of several cups.
+pub fn parse_ranking_cup(flag: &str) -> Result<String, ArgumentsError> {
+    if crate::combined::COMBINED_CUPS
+        .iter()
+        .any(|combined_cup| combined_cup.name == flag)
+    {
+        Ok(flag.to_owned())
+    } else {
+        parse_cup(flag)
+    }
+}
@@ -0,0 +1,259 @@
+// Places that are still possible with events left in the season
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::Serialize;
+
+use crate::{
+    calculate_ranking, db::Database, discovery::list_categories, ranking_places, seasons,
+    RankingEntry,
+};
+
+/// Highest score of a runner in an event.
+const MAX_SCORE: u32 = 1000;
+
+/// Number of places that get a prize.
+const PODIUM_PLACES: u32 = 3;
+
+#[derive(Debug, Serialize)]
+pub struct RunnerOutlook {
+    pub name: String,
+    pub club: String,
+    pub place: u32,
+    #[serde(rename = "totalScore")]
+    pub total_score: u32,
+    /// Total when the runner wins every remaining event.
+    #[serde(rename = "maxTotalScore")]
+    pub max_total_score: u32,
+    #[serde(rename = "bestPlace")]
+    pub best_place: u32,
+    /// `None` when runners without results yet can still pass an unknown number of times.
+    #[serde(rename = "worstPlace")]
+    pub worst_place: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryOutlook {
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    #[serde(rename = "remainingEvents")]
+    pub remaining_events: usize,
+    pub runners: Vec<RunnerOutlook>,
+    /// Nobody can reach the total of the leader anymore.
+    #[serde(rename = "winnerDecided")]
+    pub winner_decided: bool,
+    /// The runners on the podium can't change anymore, only their order.
+    #[serde(rename = "podiumDecided")]
+    pub podium_decided: bool,
+}
+
+/// Best and worst final place of every runner of the given categories.
+pub fn season_outlook(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_classes: &[String],
+    remaining_events: usize,
+) -> anyhow::Result<Vec<CategoryOutlook>> {
+    let age_classes = if age_classes.is_empty() {
+        list_categories(db, cup, season)?
+    } else {
+        age_classes.to_vec()
+    };
+    let events_count = seasons::events_count(cup, season);
+
+    let mut outlooks = vec![];
+    for age_class in age_classes {
+        let ranking =
+            calculate_ranking(db, cup.to_owned(), season, age_class.clone(), events_count)?;
+        outlooks.push(category_outlook(
+            age_class,
+            &ranking,
+            events_count,
+            remaining_events,
+        ));
+    }
+    Ok(outlooks)
+}
+
+/// Every runner is assumed to be able to score the maximum in each remaining event,
+/// while the current totals can only go up.
+fn category_outlook(
+    age_class: String,
+    ranking: &[RankingEntry],
+    events_count: usize,
+    remaining_events: usize,
+) -> CategoryOutlook {
+    let max_totals: Vec<u32> = ranking
+        .iter()
+        .map(|entry| max_total_score(entry, events_count, remaining_events))
+        .collect();
+    // A runner without results yet
+    let newcomer_max = remaining_events.min(events_count) as u32 * MAX_SCORE;
+
+    let runners: Vec<RunnerOutlook> = ranking
+        .iter()
+        .zip(ranking_places(ranking))
+        .enumerate()
+        .map(|(index, (entry, place))| {
+            let others = || {
+                ranking
+                    .iter()
+                    .zip(&max_totals)
+                    .enumerate()
+                    .filter(move |(other, _)| *other != index)
+                    .map(|(_, (other, max_total))| (other.total_score, *max_total))
+            };
+            let max_total_score = max_totals[index];
+            let best_place = 1 + others()
+                .filter(|(total, _)| *total > max_total_score)
+                .count() as u32;
+            let worst_place = if newcomer_max > entry.total_score {
+                None
+            } else {
+                Some(
+                    1 + others()
+                        .filter(|(_, max_total)| *max_total > entry.total_score)
+                        .count() as u32,
+                )
+            };
+            RunnerOutlook {
+                name: entry.name.clone(),
+                club: entry.club.clone(),
+                place,
+                total_score: entry.total_score,
+                max_total_score,
+                best_place,
+                worst_place,
+            }
+        })
+        .collect();
+
+    // Shared places are not decided
+    let winner_decided = match ranking.first() {
+        Some(leader) => {
+            newcomer_max < leader.total_score
+                && max_totals[1..]
+                    .iter()
+                    .all(|&max_total| max_total < leader.total_score)
+        }
+        None => false,
+    };
+    let newcomer_best_place = 1 + ranking
+        .iter()
+        .filter(|entry| entry.total_score > newcomer_max)
+        .count() as u32;
+    let podium_decided = runners.len() >= PODIUM_PLACES as usize
+        && newcomer_best_place > PODIUM_PLACES
+        && runners.iter().all(|runner| {
+            runner.best_place > PODIUM_PLACES
+                || runner
+                    .worst_place
+                    .is_some_and(|place| place <= PODIUM_PLACES)
+        });
+
+    CategoryOutlook {
+        age_class,
+        remaining_events,
+        runners,
+        winner_decided,
+        podium_decided,
+    }
+}
+
+// The best results so far and the maximum score in every remaining event
+fn max_total_score(entry: &RankingEntry, events_count: usize, remaining_events: usize) -> u32 {
+    let mut scores: Vec<u32> = entry.scores.iter().flat_map(|score| score.score).collect();
+    scores.extend(std::iter::repeat_n(MAX_SCORE, remaining_events));
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    scores.iter().take(events_count).sum()
+}
+
+pub fn format_outlook(outlooks: &[CategoryOutlook]) -> String {
+    let mut output = String::new();
+    for outlook in outlooks {
+        let status = if outlook.winner_decided && outlook.podium_decided {
+            "winner and podium decided"
+        } else if outlook.winner_decided {
+            "winner decided"
+        } else if outlook.podium_decided {
+            "podium decided"
+        } else {
+            "open"
+        };
+        output.push_str(&format!(
+            "{} (remaining events: {}): {}\n",
+            outlook.age_class, outlook.remaining_events, status
+        ));
+        for runner in &outlook.runners {
+            let worst_place = match runner.worst_place {
+                Some(place) => format!("{}.", place),
+                None => "-".to_owned(),
+            };
+            output.push_str(&format!(
+                "  {:>3}. {} ({}, max {}): best {}., worst {}\n",
+                runner.place,
+                runner.name,
+                runner.total_score,
+                runner.max_total_score,
+                runner.best_place,
+                worst_place
+            ));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scored_entry;
+
+    use super::category_outlook;
+
+    #[test]
+    fn open_category() {
+        let ranking = vec![
+            scored_entry("A", &[1000, 1000], 3),
+            scored_entry("B", &[900, 900], 3),
+            scored_entry("C", &[800], 3),
+        ];
+        let outlook = category_outlook("H21".to_owned(), &ranking, 3, 1);
+        let places: Vec<(u32, Option<u32>)> = outlook
+            .runners
+            .iter()
+            .map(|runner| (runner.best_place, runner.worst_place))
+            .collect();
+        // A newcomer can only reach 1000 points with one event left
+        assert_eq!(places, vec![(1, Some(2)), (1, Some(2)), (2, None)]);
+        assert!(!outlook.winner_decided);
+        assert!(!outlook.podium_decided);
+    }
+
+    #[test]
+    fn decided_category() {
+        let ranking = vec![
+            scored_entry("A", &[1000, 1000, 1000], 3),
+            scored_entry("B", &[900, 900, 900], 3),
+            scored_entry("C", &[800, 800, 800], 3),
+            scored_entry("D", &[500, 500], 3),
+        ];
+        let outlook = category_outlook("H21".to_owned(), &ranking, 3, 1);
+        assert_eq!(outlook.runners[3].max_total_score, 2000);
+        assert_eq!(outlook.runners[3].best_place, 4);
+        assert!(outlook.winner_decided);
+        assert!(outlook.podium_decided);
+    }
+
+    #[test]
+    fn counted_results_limit_the_maximum() {
+        let ranking = vec![
+            scored_entry("A", &[1000, 1000, 1000], 3),
+            scored_entry("B", &[990, 990, 990], 3),
+        ];
+        // B can replace two scores with 1000, but that is not enough to pass A
+        let outlook = category_outlook("H21".to_owned(), &ranking, 3, 2);
+        assert_eq!(outlook.runners[1].max_total_score, 2990);
+        assert_eq!(outlook.runners[1].best_place, 2);
+        assert!(outlook.winner_decided);
+    }
+}
@@ -0,0 +1,258 @@
+// Club cup
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{collections::HashMap, str::FromStr};
+
+use itertools::Itertools;
+use rusqlite::params;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    db::Database,
+    export::{event_name, write_tables, CategoryTable, Cell, ExportFormat},
+    find_age_classes, normalize_club, seasons,
+    snapshots::official_ranking,
+};
+
+/// Which runners of a club compete with each other for a place in the club score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClubGrouping {
+    /// The best runners of every age class count.
+    Category,
+    /// The best men and the best women count.
+    Gender,
+    /// The best runners of the club count.
+    Overall,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid club grouping, valid groupings are: category, gender, overall")]
+pub struct UnknownGrouping;
+
+impl FromStr for ClubGrouping {
+    type Err = UnknownGrouping;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "category" => Ok(ClubGrouping::Category),
+            "gender" => Ok(ClubGrouping::Gender),
+            "overall" => Ok(ClubGrouping::Overall),
+            _ => Err(UnknownGrouping),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct ClubScore {
+    #[serde(rename = "eventId")]
+    event_id: i64,
+    score: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClubRankingEntry {
+    club: String,
+    #[serde(rename = "totalScore")]
+    total_score: u32,
+    scores: Vec<ClubScore>,
+}
+
+/// Rank the clubs by the best `scores_count` individual scores per group in each event.
+pub fn calculate_club_ranking(
+    db: &dyn Database,
+    cup: String,
+    season: i16,
+    grouping: ClubGrouping,
+    scores_count: usize,
+) -> Result<Vec<ClubRankingEntry>, anyhow::Error> {
+    let conn = db.open()?;
+
+    // Find all events
+    let mut stmt =
+        conn.prepare("select id from Event where cup = ? and season = ? order by date asc")?;
+    let events: Vec<i64> = stmt
+        .query_map(params![cup, season], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    // Keep the best individual score of each runner in each event, with the category it
+    // was ranked in. Runners can appear in the ranking of more than one age class, on equal
+    // scores the first age class counts.
+    let events_count = seasons::events_count(&cup, season);
+    let mut best_scores: HashMap<(String, i64), (&'static str, String, u32)> = HashMap::new();
+    for age_class in find_age_classes(&conn, &cup, season)? {
+        let ranking = official_ranking(db, cup.clone(), season, age_class.clone(), events_count)?;
+        for entry in ranking {
+            let club = match normalize_club(&entry.club) {
+                Some(club) => club,
+                None => continue,
+            };
+            for score in entry.scores.iter().filter(|score| !score.compensation) {
+                if let Some(points) = score.score {
+                    let key = (entry.name.clone(), score.event_id);
+                    if best_scores
+                        .get(&key)
+                        .is_none_or(|&(_, _, best)| points > best)
+                    {
+                        best_scores.insert(key, (club, age_class.clone(), points));
+                    }
+                }
+            }
+        }
+    }
+
+    // Sum the best scores of each group per club and event
+    let club_scores = best_scores
+        .into_iter()
+        .map(|((_, event_id), (club, age_class, points))| {
+            let group = match grouping {
+                ClubGrouping::Category => age_class,
+                ClubGrouping::Gender => age_class.chars().take(1).collect(),
+                ClubGrouping::Overall => String::new(),
+            };
+            ((club, event_id, group), points)
+        })
+        .into_group_map()
+        .into_iter()
+        .map(|((club, event_id, _), points)| {
+            let score: u32 = points
+                .into_iter()
+                .sorted_unstable()
+                .rev()
+                .take(scores_count)
+                .sum();
+            ((club, event_id), score)
+        })
+        .into_grouping_map()
+        .sum();
+
+    let mut ranking: Vec<ClubRankingEntry> = Vec::new();
+    for (club, club_events) in &club_scores
+        .into_iter()
+        .map(|((club, event_id), score)| (club, (event_id, score)))
+        .sorted_by_key(|(club, _)| *club)
+        .chunk_by(|(club, _)| *club)
+    {
+        let club_events: HashMap<i64, u32> = club_events.map(|(_, score)| score).collect();
+        ranking.push(ClubRankingEntry {
+            club: club.to_owned(),
+            total_score: club_events.values().sum(),
+            scores: events
+                .iter()
+                .map(|&event_id| ClubScore {
+                    event_id,
+                    score: club_events.get(&event_id).copied(),
+                })
+                .collect(),
+        });
+    }
+    ranking.sort_by_key(|entry| entry.total_score);
+    ranking.reverse();
+    Ok(ranking)
+}
+
+/// Export the club ranking with a column per event.
+pub fn export_club_ranking(
+    db: &dyn Database,
+    ranking: &[ClubRankingEntry],
+    format: ExportFormat,
+) -> anyhow::Result<Vec<u8>> {
+    if format == ExportFormat::Json {
+        return Ok(serde_json::to_vec_pretty(ranking)?);
+    }
+    let conn = db.open()?;
+    let mut header: Vec<String> = ["Pl.", "Club"].map(String::from).to_vec();
+    if let Some(entry) = ranking.first() {
+        for score in &entry.scores {
+            header.push(event_name(&conn, score.event_id)?);
+        }
+    }
+    header.push("Totaal".to_owned());
+
+    let mut rows = vec![];
+    let mut place = 0;
+    for (index, entry) in ranking.iter().enumerate() {
+        // Clubs with the same total share a place
+        if index == 0 || ranking[index - 1].total_score != entry.total_score {
+            place = index as u32 + 1;
+        }
+        let mut row = vec![
+            match format {
+                ExportFormat::Markdown | ExportFormat::Text => Cell::Text(format!("{}.", place)),
+                _ => Cell::Number(place),
+            },
+            Cell::Text(entry.club.clone()),
+        ];
+        for score in &entry.scores {
+            row.push(score.score.map_or(Cell::Empty, Cell::Number));
+        }
+        row.push(Cell::Number(entry.total_score));
+        rows.push(row);
+    }
+    let table = CategoryTable {
+        age_class: "Clubs".to_owned(),
+        header,
+        rows,
+        text_columns: 1,
+    };
+    write_tables(&[table], format)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        export::ExportFormat,
+        testing::{add_event, add_result, database},
+    };
+
+    use super::{calculate_club_ranking, export_club_ranking, ClubGrouping};
+
+    #[test]
+    fn club_points() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        for (name, club, course, age_class, time) in [
+            ("A", "Omega", "H:Zwart Extra Lang", "H21", "01:00:00"),
+            ("B", "Omega", "H:Zwart Extra Lang", "H21", "01:20:00"),
+            ("C", "Omega", "D:Zwart Lang", "D21", "01:00:00"),
+            ("D", "hamok", "H:Zwart Extra Lang", "H21", "01:15:00"),
+            ("E", "Trol", "D:Zwart Lang", "D21", "01:30:00"),
+        ] {
+            add_result(&db, event_id, name, club, course, age_class, time);
+        }
+
+        let ranking = calculate_club_ranking(
+            &db,
+            "forest-cup".to_owned(),
+            2026,
+            ClubGrouping::Category,
+            1,
+        )
+        .unwrap();
+        let clubs: Vec<(&str, u32)> = ranking
+            .iter()
+            .map(|entry| (entry.club.as_str(), entry.total_score))
+            .collect();
+        // Only the best runner of each category counts for a club
+        assert_eq!(clubs, vec![("Omega", 2000), ("hamok", 800), ("Trol", 666)]);
+
+        let ranking =
+            calculate_club_ranking(&db, "forest-cup".to_owned(), 2026, ClubGrouping::Overall, 2)
+                .unwrap();
+        assert_eq!(ranking[0].club, "Omega");
+        assert_eq!(ranking[0].total_score, 2000);
+
+        let text = export_club_ranking(&db, &ranking, ExportFormat::Text).unwrap();
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "Clubs\n\
+             Pl.  Club   forest-cup 2026-01-17 09:00:00 UTC  Totaal\n\
+             ------------------------------------------------------\n\
+             \x201.  Omega                                2000    2000\n\
+             \x202.  hamok                                 800     800\n\
+             \x203.  Trol                                  666     666\n\
+             \n"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+// Combined Forest Cup and City Cup ranking
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use indexmap::IndexMap;
+use rusqlite::params;
+
+use crate::{calculate_ranking_with, db::Database, RankingEntry, RankingOptions, RankingScore};
+
+/// A cup whose best results count for a combined ranking.
+#[derive(Debug)]
+pub struct ContributingCup {
+    pub cup: &'static str,
+    pub events_count: usize,
+}
+
+/// Season definition of a ranking that combines several cups.
+#[derive(Debug)]
+pub struct CombinedCup {
+    pub name: &'static str,
+    pub season: i16,
+    pub cups: &'static [ContributingCup],
+}
+
+pub static COMBINED_CUPS: &[CombinedCup] = &[
+    CombinedCup {
+        name: "vlaamse-beker",
+        season: 2025,
+        cups: &[
+            ContributingCup {
+                cup: "forest-cup",
+                events_count: 4,
+            },
+            ContributingCup {
+                cup: "city-cup",
+                events_count: 4,
+            },
+        ],
+    },
+    CombinedCup {
+        name: "vlaamse-beker",
+        season: 2026,
+        cups: &[
+            ContributingCup {
+                cup: "forest-cup",
+                events_count: 4,
+            },
+            ContributingCup {
+                cup: "city-cup",
+                events_count: 4,
+            },
+        ],
+    },
+];
+
+pub fn find_combined_cup(name: &str, season: i16) -> Option<&'static CombinedCup> {
+    COMBINED_CUPS
+        .iter()
+        .find(|combined_cup| combined_cup.name == name && combined_cup.season == season)
+}
+
+/// Add up the rankings of the contributing cups of a season.
+pub(crate) fn calculate_combined_ranking(
+    db: &dyn Database,
+    combined_cup: &CombinedCup,
+    age_class: String,
+    options: &RankingOptions,
+) -> Result<Vec<RankingEntry>, anyhow::Error> {
+    let until = match options.as_of {
+        Some(as_of) => Some(as_of.until(db)?),
+        None => None,
+    };
+    let mut combined: IndexMap<String, RankingEntry> = IndexMap::new();
+    let mut previous_events: Vec<i64> = vec![];
+
+    for contributing_cup in combined_cup.cups {
+        let conn = db.open()?;
+        let mut stmt = conn.prepare(
+            "
+            select id from Event
+            where cup = ? and season = ?
+              and julianday(date) <= julianday(coalesce(?, date))
+            order by date asc
+        ",
+        )?;
+        let events: Vec<i64> = stmt
+            .query_map(
+                params![contributing_cup.cup, combined_cup.season, until],
+                |row| row.get(0),
+            )?
+            .collect::<Result<_, _>>()?;
+
+        let ranking = calculate_ranking_with(
+            db,
+            contributing_cup.cup.to_owned(),
+            combined_cup.season,
+            age_class.clone(),
+            contributing_cup.events_count,
+            options,
+        )?;
+        for entry in ranking {
+            let combined_entry =
+                combined
+                    .entry(entry.name.clone())
+                    .or_insert_with(|| RankingEntry {
+                        name: entry.name.clone(),
+                        club: entry.club.clone(),
+                        total_score: 0,
+                        scores: previous_events
+                            .iter()
+                            .map(|&event_id| RankingScore::missing(event_id))
+                            .collect(),
+                    });
+            combined_entry.total_score += entry.total_score;
+            combined_entry.scores.extend(entry.scores);
+        }
+
+        // Runners without results in this cup
+        previous_events.extend(&events);
+        for entry in combined.values_mut() {
+            for &event_id in &previous_events[entry.scores.len()..] {
+                entry.scores.push(RankingScore::missing(event_id));
+            }
+        }
+    }
+
+    let mut ranking: Vec<RankingEntry> = combined.into_values().collect();
+    ranking.sort_by_key(|entry| entry.total_score);
+    ranking.reverse();
+    Ok(ranking)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calculate_ranking, seasons,
+        testing::{add_event, add_result, database},
+    };
+
+    #[test]
+    fn merged_cups() {
+        let db = database();
+        let forest_1 = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        let forest_2 = add_event(&db, "forest-cup", 2026, "2026-02-14");
+        let city = add_event(&db, "city-cup", 2026, "2026-01-24");
+        add_result(
+            &db,
+            forest_1,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            forest_2,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            forest_2,
+            "B",
+            "Trol",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:15:00",
+        );
+        add_result(
+            &db,
+            city,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:40:00",
+        );
+        add_result(
+            &db,
+            city,
+            "C",
+            "hamok",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:32:00",
+        );
+
+        let events_count = seasons::events_count("vlaamse-beker", 2026);
+        assert_eq!(events_count, 8);
+        let ranking = calculate_ranking(
+            &db,
+            "vlaamse-beker".to_owned(),
+            2026,
+            "H21".to_owned(),
+            events_count,
+        )
+        .unwrap();
+
+        let totals: Vec<(&str, u32)> = ranking
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.total_score))
+            .collect();
+        assert_eq!(totals, vec![("A", 2800), ("C", 1000), ("B", 800)]);
+        // The events of the Forest Cup come first, runners without results in a cup get none
+        for entry in &ranking {
+            let events: Vec<i64> = entry.scores.iter().map(|score| score.event_id).collect();
+            assert_eq!(events, vec![forest_1, forest_2, city]);
+        }
+        let scores: Vec<Option<u32>> = ranking[1].scores.iter().map(|score| score.score).collect();
+        assert_eq!(scores, vec![None, None, Some(1000)]);
+    }
+}
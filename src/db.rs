@@ -3,12 +3,41 @@
 // SPDX-FileCopyrightText: 2023 Jeroen Hoekx
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use rusqlite::Connection;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OpenFlags};
 
 pub trait Database {
-    fn open(&self) -> anyhow::Result<Connection>;
+    fn open(&self) -> anyhow::Result<DatabaseConnection>;
+}
+
+/// Connection that is either owned or borrowed from a pool.
+pub enum DatabaseConnection {
+    Local(Connection),
+    Pooled(r2d2::PooledConnection<SqliteConnectionManager>),
+}
+
+impl Deref for DatabaseConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            DatabaseConnection::Local(conn) => conn,
+            DatabaseConnection::Pooled(conn) => conn,
+        }
+    }
+}
+
+impl DerefMut for DatabaseConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        match self {
+            DatabaseConnection::Local(conn) => conn,
+            DatabaseConnection::Pooled(conn) => conn,
+        }
+    }
 }
 
 pub struct LocalDatabase {
@@ -22,7 +51,57 @@ impl LocalDatabase {
 }
 
 impl Database for LocalDatabase {
-    fn open(&self) -> anyhow::Result<Connection> {
-        Ok(Connection::open(&self.path)?)
+    fn open(&self) -> anyhow::Result<DatabaseConnection> {
+        Ok(DatabaseConnection::Local(Connection::open(&self.path)?))
+    }
+}
+
+/// Pool of read-only connections shared by long-running processes.
+pub struct PooledDatabase {
+    pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl PooledDatabase {
+    pub fn read_only(path: PathBuf, size: u32) -> anyhow::Result<Self> {
+        let manager = SqliteConnectionManager::file(path).with_flags(
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        );
+        let pool = r2d2::Pool::builder().max_size(size).build(manager)?;
+        Ok(PooledDatabase { pool })
+    }
+}
+
+impl Database for PooledDatabase {
+    fn open(&self) -> anyhow::Result<DatabaseConnection> {
+        Ok(DatabaseConnection::Pooled(self.pool.get()?))
+    }
+}
+
+/// In-memory database for calculations that should not change the real database.
+pub struct ScratchDatabase {
+    uri: String,
+    // The database only lives as long as a connection to it is open
+    _conn: Connection,
+}
+
+impl ScratchDatabase {
+    /// An empty database.
+    pub fn new() -> anyhow::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let uri = format!(
+            "file:scratch-{}-{}?mode=memory&cache=shared",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let conn = Connection::open(&uri)?;
+        Ok(ScratchDatabase { uri, _conn: conn })
+    }
+}
+
+impl Database for ScratchDatabase {
+    fn open(&self) -> anyhow::Result<DatabaseConnection> {
+        Ok(DatabaseConnection::Local(Connection::open(&self.uri)?))
     }
 }
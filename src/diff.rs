@@ -0,0 +1,328 @@
+// Changes between two versions of a ranking
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+
+use crate::{
+    calculate_ranking, calculate_ranking_with, db::Database, discovery::list_categories,
+    ranking_places, seasons, RankingEntry, RankingOptions, RankingScore,
+};
+
+/// Rankings of every category of a season.
+pub type SeasonRankings = IndexMap<String, Vec<RankingEntry>>;
+
+/// Place and total of a runner in a ranking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Standing {
+    pub place: u32,
+    #[serde(rename = "totalScore")]
+    pub total_score: u32,
+}
+
+/// A runner whose standing or scores differ between two rankings.
+#[derive(Debug, Serialize)]
+pub struct RankingChange {
+    pub name: String,
+    /// `None` for runners who are new in the ranking.
+    pub before: Option<Standing>,
+    /// `None` for runners who are no longer in the ranking.
+    pub after: Option<Standing>,
+    /// The scores per event changed, even if the standing did not.
+    #[serde(rename = "scoresChanged")]
+    pub scores_changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryDiff {
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub changes: Vec<RankingChange>,
+}
+
+/// Calculate the ranking of every category of a season.
+pub fn season_rankings(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<SeasonRankings> {
+    let mut rankings = IndexMap::new();
+    for age_class in list_categories(db, cup, season)? {
+        let ranking = calculate_ranking(
+            db,
+            cup.to_owned(),
+            season,
+            age_class.clone(),
+            seasons::events_count(cup, season),
+        )?;
+        rankings.insert(age_class, ranking);
+    }
+    Ok(rankings)
+}
+
+/// Compare the rankings of a season with the rankings under other rules or policies.
+///
+/// The `options` declare the proposed rules and policies, the current rankings only share
+/// the moment they are calculated at.
+/// Without `age_classes` all categories of the season are compared.
+pub fn compare_rules(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_classes: &[String],
+    options: &RankingOptions,
+) -> anyhow::Result<Vec<CategoryDiff>> {
+    let age_classes = if age_classes.is_empty() {
+        list_categories(db, cup, season)?
+    } else {
+        age_classes.to_vec()
+    };
+    let events_count = seasons::events_count(cup, season);
+    let season_options = RankingOptions {
+        as_of: options.as_of,
+        ..Default::default()
+    };
+
+    let mut current = IndexMap::new();
+    let mut proposed = IndexMap::new();
+    for age_class in age_classes {
+        for (rankings, options) in [(&mut current, &season_options), (&mut proposed, options)] {
+            let ranking = calculate_ranking_with(
+                db,
+                cup.to_owned(),
+                season,
+                age_class.clone(),
+                events_count,
+                options,
+            )?;
+            rankings.insert(age_class.clone(), ranking);
+        }
+    }
+    Ok(diff_seasons(&current, &proposed))
+}
+
+/// Compare the rankings of all categories, categories without changes are left out.
+pub fn diff_seasons(before: &SeasonRankings, after: &SeasonRankings) -> Vec<CategoryDiff> {
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .map(|age_class| CategoryDiff {
+            age_class: age_class.clone(),
+            changes: diff_rankings(
+                before.get(age_class).map_or(&[], |ranking| ranking),
+                after.get(age_class).map_or(&[], |ranking| ranking),
+            ),
+        })
+        .filter(|diff| !diff.changes.is_empty())
+        .collect()
+}
+
+/// Runners who are new, left the ranking, or whose place, total or scores changed,
+/// in the order of the new ranking.
+pub fn diff_rankings(before: &[RankingEntry], after: &[RankingEntry]) -> Vec<RankingChange> {
+    let before_standings = standings(before);
+    let after_standings = standings(after);
+
+    let names = after
+        .iter()
+        .chain(before)
+        .map(|entry| entry.name.as_str())
+        .collect::<IndexSet<_>>();
+
+    let mut changes = vec![];
+    for name in names {
+        let before = before_standings.get(name);
+        let after = after_standings.get(name);
+        let scores_changed = match (before, after) {
+            (Some((_, before_scores)), Some((_, after_scores))) => before_scores != after_scores,
+            _ => true,
+        };
+        let before = before.map(|(standing, _)| *standing);
+        let after = after.map(|(standing, _)| *standing);
+        if before != after || scores_changed {
+            changes.push(RankingChange {
+                name: name.to_owned(),
+                before,
+                after,
+                scores_changed,
+            });
+        }
+    }
+    changes
+}
+
+fn standings(ranking: &[RankingEntry]) -> HashMap<&str, (Standing, &[RankingScore])> {
+    ranking
+        .iter()
+        .zip(ranking_places(ranking))
+        .map(|(entry, place)| {
+            let standing = Standing {
+                place,
+                total_score: entry.total_score,
+            };
+            (entry.name.as_str(), (standing, entry.scores.as_slice()))
+        })
+        .collect()
+}
+
+/// One line per change, grouped by category.
+pub fn format_diff(diffs: &[CategoryDiff]) -> String {
+    let mut output = String::new();
+    for diff in diffs {
+        output.push_str(&format!("{}\n", diff.age_class));
+        for change in &diff.changes {
+            output.push_str(&format!("  {}\n", format_change(change)));
+        }
+    }
+    output
+}
+
+fn format_change(change: &RankingChange) -> String {
+    match (change.before, change.after) {
+        (None, Some(after)) => format!(
+            "new    {}: {}. ({})",
+            change.name, after.place, after.total_score
+        ),
+        (Some(before), None) => format!(
+            "gone   {}: was {}. ({})",
+            change.name, before.place, before.total_score
+        ),
+        (Some(before), Some(after)) => {
+            let movement = match after.place.cmp(&before.place) {
+                Ordering::Less => format!("up {}", before.place - after.place),
+                Ordering::Greater => format!("down {}", after.place - before.place),
+                Ordering::Equal => "same".to_owned(),
+            };
+            let mut line = format!(
+                "{:<6} {}: {}. -> {}.",
+                movement, change.name, before.place, after.place
+            );
+            if before.total_score != after.total_score {
+                line.push_str(&format!(
+                    ", {} -> {}",
+                    before.total_score, after.total_score
+                ));
+            } else if change.scores_changed {
+                line.push_str(", scores changed");
+            }
+            line
+        }
+        (None, None) => unreachable!("a change is in at least one of the rankings"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        seasons::ReferencePolicy,
+        testing::{add_event, add_result, database, entry},
+        RankingOptions,
+    };
+
+    use super::{compare_rules, diff_rankings, format_change, Standing};
+
+    #[test]
+    fn unchanged() {
+        let before = vec![entry("A", 3000), entry("B", 2900)];
+        let after = vec![entry("A", 3000), entry("B", 2900)];
+        assert!(diff_rankings(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn changes() {
+        let before = vec![entry("A", 3000), entry("B", 2900), entry("D", 50)];
+        let after = vec![entry("B", 3100), entry("A", 3000), entry("C", 100)];
+        let changes = diff_rankings(&before, &after);
+        let names: Vec<&str> = changes.iter().map(|change| change.name.as_str()).collect();
+        assert_eq!(names, vec!["B", "A", "C", "D"]);
+        assert_eq!(
+            changes[0].after,
+            Some(Standing {
+                place: 1,
+                total_score: 3100
+            })
+        );
+        assert_eq!(changes[2].before, None);
+        assert_eq!(changes[3].after, None);
+
+        let lines: Vec<String> = changes.iter().map(format_change).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "up 1   B: 2. -> 1., 2900 -> 3100",
+                "down 1 A: 1. -> 2.",
+                "new    C: 3. (100)",
+                "gone   D: was 3. (50)",
+            ]
+        );
+    }
+
+    #[test]
+    fn other_reference_policy() {
+        let db = database();
+        let event = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        add_result(
+            &db,
+            event,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            event,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:50:00",
+        );
+        add_result(
+            &db,
+            event,
+            "X",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "D21",
+            "00:40:00",
+        );
+        let age_classes = vec!["H21".to_owned()];
+
+        let diffs = compare_rules(
+            &db,
+            "forest-cup",
+            2026,
+            &age_classes,
+            &RankingOptions::default(),
+        )
+        .unwrap();
+        assert!(diffs.is_empty());
+
+        let options = RankingOptions {
+            reference: Some(ReferencePolicy::AllFinishers),
+            ..Default::default()
+        };
+        let diffs = compare_rules(&db, "forest-cup", 2026, &age_classes, &options).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].age_class, "H21");
+        let changes: Vec<(&str, u32, u32)> = diffs[0]
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    change.name.as_str(),
+                    change.before.unwrap().total_score,
+                    change.after.unwrap().total_score,
+                )
+            })
+            .collect();
+        assert_eq!(changes, vec![("B", 1000, 800), ("A", 833, 666)]);
+    }
+}
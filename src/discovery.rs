@@ -0,0 +1,192 @@
+// Cups, seasons, events and categories stored in the database
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::{combined::find_combined_cup, db::Database, find_age_classes, seasons};
+
+#[derive(Debug, Serialize)]
+pub struct SeasonInfo {
+    pub season: i16,
+    /// Number of results that count for the ranking.
+    #[serde(rename = "eventsCount")]
+    pub events_count: usize,
+    pub events: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseInfo {
+    pub name: String,
+    pub distance: u32,
+    pub climb: u32,
+    pub runners: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventInfo {
+    pub id: i64,
+    pub name: String,
+    pub date: String,
+    pub location: String,
+    pub courses: Vec<CourseInfo>,
+}
+
+pub fn list_cups(db: &dyn Database) -> anyhow::Result<Vec<String>> {
+    let conn = db.open()?;
+    let mut stmt = conn.prepare("select distinct cup from Event order by cup asc")?;
+    let cups = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(cups)
+}
+
+pub fn list_seasons(db: &dyn Database, cup: &str) -> anyhow::Result<Vec<SeasonInfo>> {
+    let conn = db.open()?;
+    let mut stmt = conn.prepare(
+        "
+        select cast(season as integer), count(*)
+        from Event
+        where cup = ?
+        group by season
+        order by season asc
+    ",
+    )?;
+    let seasons = stmt
+        .query_map(params![cup], |row| {
+            let season = row.get(0)?;
+            Ok(SeasonInfo {
+                season,
+                events_count: seasons::events_count(cup, season),
+                events: row.get(1)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(seasons)
+}
+
+/// Events of a season in the order of the ranking scores.
+pub fn list_events(db: &dyn Database, cup: &str, season: i16) -> anyhow::Result<Vec<EventInfo>> {
+    let conn = db.open()?;
+    let mut events = match find_combined_cup(cup, season) {
+        Some(combined_cup) => {
+            let mut events = vec![];
+            for contributing_cup in combined_cup.cups {
+                events.extend(find_events(&conn, contributing_cup.cup, season)?);
+            }
+            events
+        }
+        None => find_events(&conn, cup, season)?,
+    };
+
+    let mut stmt = conn.prepare(
+        "
+        select
+            Course.name,
+            Course.distance,
+            Course.climb,
+            (select count(*) from Result
+             where Result.event_id = Course.event_id and Result.category_name = Course.name)
+        from Course
+        where Course.event_id = ?
+        order by Course.name asc
+    ",
+    )?;
+    for event in events.iter_mut() {
+        event.courses = stmt
+            .query_map(params![event.id], |row| {
+                Ok(CourseInfo {
+                    name: row.get(0)?,
+                    distance: row.get(1)?,
+                    climb: row.get(2)?,
+                    runners: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(events)
+}
+
+fn find_events(conn: &Connection, cup: &str, season: i16) -> anyhow::Result<Vec<EventInfo>> {
+    let mut stmt = conn.prepare(
+        "
+        select id, name, date, location
+        from Event
+        where cup = ? and season = ?
+        order by date asc
+    ",
+    )?;
+    let events = stmt
+        .query_map(params![cup, season], |row| {
+            Ok(EventInfo {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                date: row.get(2)?,
+                location: row.get(3)?,
+                courses: vec![],
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(events)
+}
+
+/// Age classes with results in a season, in the order of the ranking pages.
+pub fn list_categories(db: &dyn Database, cup: &str, season: i16) -> anyhow::Result<Vec<String>> {
+    let conn = db.open()?;
+    let age_classes = match find_combined_cup(cup, season) {
+        Some(combined_cup) => {
+            let mut age_classes = vec![];
+            for contributing_cup in combined_cup.cups {
+                age_classes.extend(find_age_classes(&conn, contributing_cup.cup, season)?);
+            }
+            age_classes
+        }
+        None => find_age_classes(&conn, cup, season)?,
+    };
+    Ok(age_classes
+        .into_iter()
+        .unique()
+        .sorted_by_key(|age_class| category_order(age_class))
+        .collect())
+}
+
+static AGE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)").unwrap());
+
+// D21 and H21 first, then the ladies and the men, youth before adults
+pub(crate) fn category_order(age_class: &str) -> (bool, String, bool, u32, String) {
+    let gender = age_class.chars().take(1).collect();
+    let age = AGE_RE
+        .captures(age_class)
+        .and_then(|captures| captures[1].parse().ok())
+        .unwrap_or(u32::MAX);
+    (
+        age != 21,
+        gender,
+        !age_class.contains('-'),
+        age,
+        age_class.to_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::category_order;
+
+    #[test]
+    fn order() {
+        let categories = ["H40", "D-10", "H-18", "H21", "D35", "D21", "H. Open"];
+        assert_eq!(
+            categories
+                .into_iter()
+                .sorted_by_key(|c| category_order(c))
+                .collect_vec(),
+            vec!["D21", "H21", "D-10", "D35", "H-18", "H40", "H. Open"]
+        );
+    }
+}
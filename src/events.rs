@@ -0,0 +1,332 @@
+// Event administration
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::bail;
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::{cli::parse_cup, db::Database, list_organisers, Organiser};
+
+#[derive(Debug, Serialize)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub cup: String,
+    pub season: i16,
+    pub name: String,
+    pub location: String,
+    pub date: DateTime<Utc>,
+    pub results: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgeClassResults {
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub results: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventDetails {
+    pub event: StoredEvent,
+    #[serde(rename = "ageClasses")]
+    pub age_classes: Vec<AgeClassResults>,
+    pub organisers: Vec<Organiser>,
+}
+
+/// Changes to the description of an event, fields that are `None` are kept.
+#[derive(Debug, Default)]
+pub struct EventUpdate {
+    pub name: Option<String>,
+    pub location: Option<String>,
+    /// The new day of the event, the start time is kept.
+    pub date: Option<NaiveDate>,
+}
+
+/// Rows removed with an event.
+#[derive(Debug)]
+pub struct DeletedEvent {
+    pub results: usize,
+    pub courses: usize,
+    pub organisers: usize,
+    pub runners: usize,
+}
+
+const EVENT_COLUMNS: &str = "
+    select
+        Event.id,
+        Event.cup,
+        cast(Event.season as integer),
+        Event.name,
+        Event.location,
+        Event.date,
+        (select count(*) from Result where Result.event_id = Event.id)
+    from Event
+";
+
+fn read_event(row: &rusqlite::Row) -> rusqlite::Result<StoredEvent> {
+    Ok(StoredEvent {
+        id: row.get(0)?,
+        cup: row.get(1)?,
+        season: row.get(2)?,
+        name: row.get(3)?,
+        location: row.get(4)?,
+        date: row.get(5)?,
+        results: row.get(6)?,
+    })
+}
+
+/// List the stored events, optionally of one cup and season.
+pub fn list_stored_events(
+    db: &dyn Database,
+    cup: Option<&str>,
+    season: Option<i16>,
+) -> anyhow::Result<Vec<StoredEvent>> {
+    let conn = db.open()?;
+    let mut stmt = conn.prepare(&format!(
+        "{}
+        where (?1 is null or Event.cup = ?1)
+          and (?2 is null or cast(Event.season as integer) = ?2)
+        order by Event.date asc
+        ",
+        EVENT_COLUMNS
+    ))?;
+    let events = stmt
+        .query_map(params![cup, season], read_event)?
+        .collect::<Result<_, _>>()?;
+    Ok(events)
+}
+
+pub fn event_details(db: &dyn Database, event_id: i64) -> anyhow::Result<EventDetails> {
+    let conn = db.open()?;
+    let event = find_event(&conn, event_id)?;
+
+    let mut stmt = conn.prepare(
+        "
+        select age_class, count(*)
+        from Result
+        where event_id = ?
+        group by age_class
+        order by age_class asc
+    ",
+    )?;
+    let age_classes = stmt
+        .query_map(params![event_id], |row| {
+            Ok(AgeClassResults {
+                age_class: row.get(0)?,
+                results: row.get(1)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+
+    let organisers = list_organisers(db, &event.cup, event.season)?
+        .into_iter()
+        .filter(|organiser| organiser.event_id == event_id)
+        .collect();
+
+    Ok(EventDetails {
+        event,
+        age_classes,
+        organisers,
+    })
+}
+
+pub fn update_event(db: &dyn Database, event_id: i64, update: EventUpdate) -> anyhow::Result<()> {
+    let conn = db.open()?;
+    let event = find_event(&conn, event_id)?;
+    let date = match update.date {
+        Some(date) => date.and_time(event.date.time()).and_utc(),
+        None => event.date,
+    };
+    conn.execute(
+        "update Event set name = ?, location = ?, date = ? where id = ?",
+        params![
+            update.name.unwrap_or(event.name),
+            update.location.unwrap_or(event.location),
+            date,
+            event_id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Move an event and its results to another cup or season.
+pub fn move_event(db: &dyn Database, event_id: i64, cup: &str, season: i16) -> anyhow::Result<()> {
+    parse_cup(cup)?;
+    let conn = db.open()?;
+    find_event(&conn, event_id)?;
+    conn.execute(
+        "update Event set cup = ?, season = ? where id = ?",
+        params![cup, season.to_string(), event_id],
+    )?;
+    Ok(())
+}
+
+/// Delete an event with its results, courses and organisers.
+///
+/// Runners without any results or organised events left are removed as well.
+pub fn delete_event(db: &dyn Database, event_id: i64) -> anyhow::Result<DeletedEvent> {
+    let mut conn = db.open()?;
+    find_event(&conn, event_id)?;
+
+    let tx = conn.transaction()?;
+    let results = tx.execute("delete from Result where event_id = ?", params![event_id])?;
+    let courses = tx.execute("delete from Course where event_id = ?", params![event_id])?;
+    let organisers = tx.execute(
+        "delete from Organiser where event_id = ?",
+        params![event_id],
+    )?;
+    tx.execute("delete from Event where id = ?", params![event_id])?;
+    let runners = tx.execute(
+        "
+        delete from Runner
+        where id not in (select runner_id from Result)
+          and id not in (select runner_id from Organiser)
+          and id not in (select runner_id from ClassChange)
+          and id not in (select runner_id from Registration)
+    ",
+        [],
+    )?;
+    tx.commit()?;
+
+    Ok(DeletedEvent {
+        results,
+        courses,
+        organisers,
+        runners,
+    })
+}
+
+fn find_event(conn: &Connection, event_id: i64) -> anyhow::Result<StoredEvent> {
+    let event = conn
+        .query_row(
+            &format!("{} where Event.id = ?", EVENT_COLUMNS),
+            params![event_id],
+            read_event,
+        )
+        .optional()?;
+    match event {
+        Some(event) => Ok(event),
+        None => bail!("Event {} does not exist", event_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::params;
+
+    use crate::{
+        calculate_ranking,
+        db::Database,
+        store_organiser,
+        testing::{add_event, add_result, database},
+    };
+
+    use super::{delete_event, move_event};
+
+    fn count(db: &dyn Database, table: &str, event_id: i64) -> i64 {
+        db.open()
+            .unwrap()
+            .query_row(
+                &format!("select count(*) from {} where event_id = ?", table),
+                params![event_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    fn runners(db: &dyn Database) -> Vec<String> {
+        let conn = db.open().unwrap();
+        let mut stmt = conn
+            .prepare("select name from Runner order by name")
+            .unwrap();
+        let names = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        names
+    }
+
+    #[test]
+    fn delete_with_orphans() {
+        let db = database();
+        let kept = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        let deleted = add_event(&db, "forest-cup", 2026, "2026-02-14");
+        for event_id in [kept, deleted] {
+            add_result(
+                &db,
+                event_id,
+                "A",
+                "Omega",
+                "H:Zwart Extra Lang",
+                "H21",
+                "01:00:00",
+            );
+            db.open()
+                .unwrap()
+                .execute(
+                    "insert into Course (event_id, name, distance, climb) values (?, ?, 9000, 200)",
+                    params![event_id, "H:Zwart Extra Lang"],
+                )
+                .unwrap();
+        }
+        add_result(
+            &db,
+            deleted,
+            "B",
+            "Trol",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:10:00",
+        );
+        store_organiser(&db, deleted, "C", "hamok", "course builder").unwrap();
+
+        let removed = delete_event(&db, deleted).unwrap();
+        assert_eq!(
+            (
+                removed.results,
+                removed.courses,
+                removed.organisers,
+                removed.runners
+            ),
+            (2, 1, 1, 2)
+        );
+        for table in ["Result", "Course", "Organiser"] {
+            assert_eq!(count(&db, table, deleted), 0);
+        }
+        assert_eq!(count(&db, "Result", kept), 1);
+        assert_eq!(runners(&db), vec!["A"]);
+        assert!(delete_event(&db, deleted).is_err());
+
+        let ranking =
+            calculate_ranking(&db, "forest-cup".to_owned(), 2026, "H21".to_owned(), 4).unwrap();
+        assert_eq!(ranking.len(), 1);
+        let events: Vec<i64> = ranking[0]
+            .scores
+            .iter()
+            .map(|score| score.event_id)
+            .collect();
+        assert_eq!(events, vec![kept]);
+    }
+
+    #[test]
+    fn move_to_an_existing_cup() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        assert!(move_event(&db, event_id, "forest cup", 2026).is_err());
+        assert!(move_event(&db, event_id, "vlaamse-beker", 2026).is_err());
+        move_event(&db, event_id, "city-cup", 2027).unwrap();
+        let (cup, season): (String, String) = db
+            .open()
+            .unwrap()
+            .query_row(
+                "select cup, season from Event where id = ?",
+                params![event_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((cup.as_str(), season.as_str()), ("city-cup", "2027"));
+    }
+}
@@ -0,0 +1,518 @@
+// How the scores of a ranking were derived
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::bail;
+use chrono::NaiveTime;
+use serde::Serialize;
+
+use crate::{
+    combined::find_combined_cup, db::Database, rank, ranking_places, rule_version,
+    seasons::reference_policy, total_seconds, Performance, RankingContext, RankingOptions,
+};
+
+/// The fastest time of a course and the classes it was taken from.
+#[derive(Debug, Serialize)]
+pub struct CourseExplanation {
+    #[serde(rename = "eventId")]
+    pub event_id: i64,
+    pub course: String,
+    /// The category whose filter selected the results, differs from the ranked category
+    /// for results before a class change.
+    #[serde(rename = "rankedAs")]
+    pub ranked_as: String,
+    /// The reference time of the course, the fastest time of the reference policy.
+    #[serde(rename = "fastestTime")]
+    pub fastest_time: Option<NaiveTime>,
+    #[serde(rename = "includedClasses")]
+    pub included_classes: BTreeSet<String>,
+    #[serde(rename = "ignoredClasses")]
+    pub ignored_classes: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventExplanation {
+    #[serde(rename = "eventId")]
+    pub event_id: i64,
+    pub course: Option<String>,
+    #[serde(rename = "ageClass")]
+    pub age_class: Option<String>,
+    pub time: Option<NaiveTime>,
+    #[serde(rename = "fastestTime")]
+    pub fastest_time: Option<NaiveTime>,
+    pub score: Option<u32>,
+    pub place: Option<u32>,
+    /// Points for organising the event.
+    pub compensation: bool,
+    /// The result was run in this class before changing to the ranked category.
+    #[serde(rename = "classChange")]
+    pub class_change: Option<String>,
+    /// The result was run in a shorter course than the class should run.
+    #[serde(rename = "wrongCourse")]
+    pub wrong_course: bool,
+    pub counted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunnerExplanation {
+    pub name: String,
+    pub club: String,
+    pub place: u32,
+    #[serde(rename = "totalScore")]
+    pub total_score: u32,
+    pub events: Vec<EventExplanation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankingExplanation {
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub rules: String,
+    /// Who sets the reference time of a course.
+    pub reference: String,
+    #[serde(rename = "eventsCount")]
+    pub events_count: usize,
+    pub courses: Vec<CourseExplanation>,
+    pub runners: Vec<RunnerExplanation>,
+}
+
+#[derive(Debug)]
+struct ScoredPerformance {
+    ranked_as: String,
+    name: String,
+    event_id: i64,
+    course: String,
+    age_class: String,
+    time: NaiveTime,
+    score: u32,
+}
+
+/// Collects the steps of a ranking calculation.
+#[derive(Debug, Default)]
+pub(crate) struct Explanation {
+    courses: Vec<CourseExplanation>,
+    performances: Vec<ScoredPerformance>,
+    class_changes: HashSet<(String, i64)>,
+    wrong_courses: HashSet<(String, i64)>,
+}
+
+impl Explanation {
+    fn course(&mut self, ranked_as: &str, event_id: i64, course: &str) -> &mut CourseExplanation {
+        let index = match self.courses.iter().position(|explanation| {
+            explanation.ranked_as == ranked_as
+                && explanation.event_id == event_id
+                && explanation.course == course
+        }) {
+            Some(index) => index,
+            None => {
+                self.courses.push(CourseExplanation {
+                    event_id,
+                    course: course.to_owned(),
+                    ranked_as: ranked_as.to_owned(),
+                    fastest_time: None,
+                    included_classes: BTreeSet::new(),
+                    ignored_classes: BTreeSet::new(),
+                });
+                self.courses.len() - 1
+            }
+        };
+        &mut self.courses[index]
+    }
+
+    pub(crate) fn filter(&mut self, ranked_as: &str, performance: &Performance, ignored: bool) {
+        let course = self.course(ranked_as, performance.event_id, &performance.category_name);
+        if ignored {
+            course.ignored_classes.insert(performance.age_class.clone());
+        } else {
+            course
+                .included_classes
+                .insert(performance.age_class.clone());
+        }
+    }
+
+    pub(crate) fn scores(
+        &mut self,
+        ranked_as: &str,
+        fastest_times: &HashMap<(i64, String), u32>,
+        performances: &[Performance],
+    ) {
+        for ((event_id, course), &fastest) in fastest_times {
+            self.course(ranked_as, *event_id, course).fastest_time =
+                NaiveTime::from_num_seconds_from_midnight_opt(fastest, 0);
+        }
+        for performance in performances {
+            self.performances.push(ScoredPerformance {
+                ranked_as: ranked_as.to_owned(),
+                name: performance.name.clone(),
+                event_id: performance.event_id,
+                course: performance.category_name.clone(),
+                age_class: performance.age_class.clone(),
+                time: performance.time,
+                score: performance.score,
+            });
+        }
+    }
+
+    pub(crate) fn class_change(&mut self, performance: &Performance) {
+        self.class_changes
+            .insert((performance.name.clone(), performance.event_id));
+    }
+
+    pub(crate) fn wrong_course(&mut self, performance: &Performance) {
+        self.wrong_courses
+            .insert((performance.name.clone(), performance.event_id));
+    }
+}
+
+/// Calculate a ranking and explain the score of every runner in every event.
+pub fn explain_ranking(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+    options: &RankingOptions,
+) -> anyhow::Result<RankingExplanation> {
+    if find_combined_cup(cup, season).is_some() {
+        bail!("Explain the rankings of the cups that make up {}", cup);
+    }
+    let rules = options.rules.unwrap_or_else(|| rule_version(cup, season));
+    let reference = options
+        .reference
+        .unwrap_or_else(|| reference_policy(cup, season));
+    let mut context = RankingContext::new(db, options)?;
+    context.explanation = Some(Explanation::default());
+    let ranking = rank(
+        db,
+        cup.to_owned(),
+        season,
+        age_class.to_owned(),
+        events_count,
+        rules,
+        &mut context,
+    )?;
+    let mut explanation = context.explanation.unwrap_or_default();
+    explanation.courses.sort_by(|a, b| {
+        (a.event_id, &a.course, &a.ranked_as).cmp(&(b.event_id, &b.course, &b.ranked_as))
+    });
+
+    let runners = ranking
+        .iter()
+        .zip(ranking_places(&ranking))
+        .map(|(entry, place)| RunnerExplanation {
+            name: entry.name.clone(),
+            club: entry.club.clone(),
+            place,
+            total_score: entry.total_score,
+            events: entry
+                .scores
+                .iter()
+                .map(|score| {
+                    // The performance the score was taken from, preferably in the ranked category
+                    let performance = explanation
+                        .performances
+                        .iter()
+                        .filter(|performance| {
+                            performance.name == entry.name
+                                && performance.event_id == score.event_id
+                                && Some(performance.score) == score.score
+                        })
+                        .min_by_key(|performance| performance.ranked_as != age_class);
+                    let fastest_time = performance.and_then(|performance| {
+                        explanation
+                            .courses
+                            .iter()
+                            .find(|course| {
+                                course.ranked_as == performance.ranked_as
+                                    && course.event_id == performance.event_id
+                                    && course.course == performance.course
+                            })
+                            .and_then(|course| course.fastest_time)
+                    });
+                    EventExplanation {
+                        event_id: score.event_id,
+                        course: performance.map(|performance| performance.course.clone()),
+                        age_class: performance.map(|performance| performance.age_class.clone()),
+                        time: performance.map(|performance| performance.time),
+                        fastest_time,
+                        score: score.score,
+                        place: score.place,
+                        compensation: score.compensation,
+                        class_change: performance
+                            .filter(|performance| {
+                                performance.ranked_as != age_class
+                                    && explanation
+                                        .class_changes
+                                        .contains(&(performance.name.clone(), performance.event_id))
+                            })
+                            .map(|performance| performance.ranked_as.clone()),
+                        wrong_course: performance.is_some_and(|performance| {
+                            explanation
+                                .wrong_courses
+                                .contains(&(performance.name.clone(), performance.event_id))
+                        }),
+                        counted: score.counted,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(RankingExplanation {
+        age_class: age_class.to_owned(),
+        rules: rules.to_string(),
+        reference: reference.to_string(),
+        events_count,
+        courses: explanation.courses,
+        runners,
+    })
+}
+
+pub fn format_explanation(explanation: &RankingExplanation) -> String {
+    let mut output = format!(
+        "{}, rules {}, reference {}, best {} results count\n",
+        explanation.age_class, explanation.rules, explanation.reference, explanation.events_count
+    );
+    output.push_str("Courses\n");
+    for course in &explanation.courses {
+        let fastest_time = course
+            .fastest_time
+            .map_or("-".to_owned(), |time| time.to_string());
+        output.push_str(&format!(
+            "  event {} {} as {}: reference {}",
+            course.event_id, course.course, course.ranked_as, fastest_time
+        ));
+        if !course.included_classes.is_empty() {
+            output.push_str(&format!(
+                ", included {}",
+                course
+                    .included_classes
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ));
+        }
+        if !course.ignored_classes.is_empty() {
+            output.push_str(&format!(
+                ", ignored {}",
+                course
+                    .ignored_classes
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ));
+        }
+        output.push('\n');
+    }
+    for runner in &explanation.runners {
+        output.push_str(&format!(
+            "{}. {} ({}): {}\n",
+            runner.place, runner.name, runner.club, runner.total_score
+        ));
+        for event in &runner.events {
+            output.push_str(&format!(
+                "  event {}: {}\n",
+                event.event_id,
+                explain_event(event)
+            ));
+        }
+    }
+    output
+}
+
+fn explain_event(event: &EventExplanation) -> String {
+    let Some(score) = event.score else {
+        return "no result".to_owned();
+    };
+    let mut line = if event.compensation {
+        format!("{} points for organising", score)
+    } else {
+        match (
+            &event.course,
+            &event.age_class,
+            event.time,
+            event.fastest_time,
+        ) {
+            (Some(course), Some(age_class), Some(time), Some(fastest_time))
+                if event.wrong_course =>
+            {
+                format!(
+                    "{} in {} as {}, 1000 * {} / {} = {}, wrong course counts {}",
+                    time,
+                    course,
+                    age_class,
+                    fastest_time,
+                    time,
+                    1000 * total_seconds(fastest_time) / total_seconds(time),
+                    score
+                )
+            }
+            (Some(course), Some(age_class), Some(time), Some(fastest_time)) => format!(
+                "{} in {} as {}, 1000 * {} / {} = {}",
+                time, course, age_class, fastest_time, time, score
+            ),
+            _ => format!("{} points", score),
+        }
+    };
+    if let Some(from_class) = &event.class_change {
+        line.push_str(&format!(", run in {} before the class change", from_class));
+    }
+    if !event.counted {
+        line.push_str(", dropped");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use crate::{
+        testing::{add_event, add_result, database},
+        RankingOptions,
+    };
+
+    use super::{explain_event, explain_ranking, EventExplanation};
+
+    fn event(score: Option<u32>) -> EventExplanation {
+        EventExplanation {
+            event_id: 1,
+            course: Some("H:Zwart Lang".to_owned()),
+            age_class: Some("H40".to_owned()),
+            time: NaiveTime::from_hms_opt(1, 4, 24),
+            fastest_time: NaiveTime::from_hms_opt(1, 3, 0),
+            score,
+            place: Some(2),
+            compensation: false,
+            class_change: None,
+            wrong_course: false,
+            counted: true,
+        }
+    }
+
+    #[test]
+    fn explained_events() {
+        assert_eq!(
+            explain_event(&event(Some(978))),
+            "01:04:24 in H:Zwart Lang as H40, 1000 * 01:03:00 / 01:04:24 = 978"
+        );
+        assert_eq!(explain_event(&event(None)), "no result");
+
+        let mut dropped = event(Some(978));
+        dropped.class_change = Some("H35".to_owned());
+        dropped.counted = false;
+        assert!(explain_event(&dropped).ends_with(", run in H35 before the class change, dropped"));
+
+        let mut wrong_course = event(Some(782));
+        wrong_course.wrong_course = true;
+        assert!(explain_event(&wrong_course).ends_with("= 978, wrong course counts 782"));
+
+        let mut organised = event(Some(950));
+        organised.compensation = true;
+        assert_eq!(explain_event(&organised), "950 points for organising");
+    }
+
+    #[test]
+    fn explained_ranking() {
+        let db = database();
+        let first = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        let second = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            first,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            first,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H35",
+            "00:50:00",
+        );
+        add_result(
+            &db,
+            first,
+            "X",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "D21",
+            "00:40:00",
+        );
+        add_result(
+            &db,
+            second,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:45:00",
+        );
+
+        let explanation = explain_ranking(
+            &db,
+            "forest-cup",
+            2026,
+            "H21",
+            1,
+            &RankingOptions::default(),
+        )
+        .unwrap();
+        // The courses of H-20 are explained too, for runners who changed to H21
+        let courses: Vec<_> = explanation
+            .courses
+            .iter()
+            .filter(|course| course.ranked_as == "H21")
+            .map(|course| {
+                (
+                    course.event_id,
+                    course.ranked_as.as_str(),
+                    course.fastest_time,
+                    course.included_classes.iter().cloned().collect::<Vec<_>>(),
+                    course.ignored_classes.iter().cloned().collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            courses,
+            vec![
+                (
+                    first,
+                    "H21",
+                    NaiveTime::from_hms_opt(0, 50, 0),
+                    vec!["H21".to_owned(), "H35".to_owned()],
+                    vec!["D21".to_owned()],
+                ),
+                (
+                    second,
+                    "H21",
+                    NaiveTime::from_hms_opt(0, 45, 0),
+                    vec!["H21".to_owned()],
+                    vec![],
+                ),
+            ]
+        );
+
+        let runner = explanation
+            .runners
+            .iter()
+            .find(|runner| runner.name == "A")
+            .unwrap();
+        assert_eq!(runner.total_score, 1000);
+        let dropped = &runner.events[0];
+        assert_eq!(dropped.score, Some(833));
+        assert_eq!(dropped.fastest_time, NaiveTime::from_hms_opt(0, 50, 0));
+        assert!(!dropped.counted);
+        assert!(runner.events[1].counted);
+        assert!(explanation.runners.iter().all(|runner| runner.name != "X"));
+    }
+}
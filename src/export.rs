@@ -0,0 +1,578 @@
+// Rankings as CSV, TSV and spreadsheet workbooks
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::str::FromStr;
+
+use anyhow::bail;
+
+use indexmap::IndexMap;
+use rusqlite::{params, Connection, OptionalExtension};
+use rust_xlsxwriter::{Format, Workbook};
+use thiserror::Error;
+
+use crate::{
+    db::Database, ranking_places, snapshots::official_ranking_with, RankingEntry, RankingOptions,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    /// Workbook with one sheet per category.
+    Xlsx,
+    Markdown,
+    /// The rankings by category.
+    Json,
+    /// Aligned table for the terminal.
+    Text,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid export format, valid formats are: csv, tsv, xlsx, markdown, json, text")]
+pub struct UnknownFormat;
+
+impl FromStr for ExportFormat {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "tsv" => Ok(ExportFormat::Tsv),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            "markdown" => Ok(ExportFormat::Markdown),
+            "json" => Ok(ExportFormat::Json),
+            "text" => Ok(ExportFormat::Text),
+            _ => Err(UnknownFormat),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Text => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+pub(crate) enum Cell {
+    Text(String),
+    Number(u32),
+    Flag(bool),
+    Empty,
+}
+
+pub(crate) struct CategoryTable {
+    pub(crate) age_class: String,
+    pub(crate) header: Vec<String>,
+    pub(crate) rows: Vec<Vec<Cell>>,
+    /// Columns after the place that hold text, they are aligned to the left.
+    pub(crate) text_columns: usize,
+}
+
+/// Export the rankings of the given categories of a season.
+///
+/// Without options the frozen official rankings are exported.
+/// Every runner gets a row with the place, name, club, the score, place and counted flag
+/// of every event and the total score.
+/// CSV and TSV exports start each row with the category.
+pub fn export_rankings(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_classes: &[String],
+    events_count: usize,
+    options: &RankingOptions,
+    format: ExportFormat,
+) -> anyhow::Result<Vec<u8>> {
+    let mut rankings = IndexMap::new();
+    for age_class in age_classes {
+        let ranking = official_ranking_with(
+            db,
+            cup.to_owned(),
+            season,
+            age_class.to_owned(),
+            events_count,
+            options,
+        )?;
+        rankings.insert(age_class.to_owned(), ranking);
+    }
+
+    if format == ExportFormat::Json {
+        return Ok(serde_json::to_vec_pretty(&rankings)?);
+    }
+    let mut tables = vec![];
+    for (age_class, ranking) in &rankings {
+        tables.push(match format {
+            ExportFormat::Markdown | ExportFormat::Text => {
+                summary_table(db, age_class, ranking, format)?
+            }
+            _ => category_table(db, age_class, ranking)?,
+        });
+    }
+    let mut output = write_tables(&tables, format)?;
+    match format {
+        ExportFormat::Markdown => {
+            output.extend_from_slice(b"Doorstreepte resultaten tellen niet mee.\n")
+        }
+        ExportFormat::Text => output.extend_from_slice(b"[...] telt niet mee\n"),
+        _ => {}
+    }
+    Ok(output)
+}
+
+/// Tables in any format but JSON, which serializes the rankings themselves.
+pub(crate) fn write_tables(
+    tables: &[CategoryTable],
+    format: ExportFormat,
+) -> anyhow::Result<Vec<u8>> {
+    match format {
+        ExportFormat::Csv => write_delimited(tables, b','),
+        ExportFormat::Tsv => write_delimited(tables, b'\t'),
+        ExportFormat::Xlsx => write_workbook(tables),
+        ExportFormat::Markdown => Ok(write_markdown(tables).into_bytes()),
+        ExportFormat::Text => Ok(write_text(tables).into_bytes()),
+        ExportFormat::Json => bail!("JSON is not written as tables"),
+    }
+}
+
+pub(crate) fn event_name(conn: &Connection, event_id: i64) -> anyhow::Result<String> {
+    Ok(conn
+        .query_row(
+            "select name from Event where id = ?",
+            params![event_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_else(|| event_id.to_string()))
+}
+
+fn category_table(
+    db: &dyn Database,
+    age_class: &str,
+    ranking: &[RankingEntry],
+) -> anyhow::Result<CategoryTable> {
+    let conn = db.open()?;
+    let mut header: Vec<String> = ["Plaats", "Naam", "Club"].map(String::from).to_vec();
+    if let Some(entry) = ranking.first() {
+        for score in &entry.scores {
+            let event_name = event_name(&conn, score.event_id)?;
+            header.push(format!("{} punten", event_name));
+            header.push(format!("{} plaats", event_name));
+            header.push(format!("{} telt", event_name));
+        }
+    }
+    header.push("Totaal".to_owned());
+
+    let rows = ranking
+        .iter()
+        .zip(ranking_places(ranking))
+        .map(|(entry, place)| {
+            let mut row = vec![
+                Cell::Number(place),
+                Cell::Text(entry.name.clone()),
+                Cell::Text(entry.club.clone()),
+            ];
+            for score in &entry.scores {
+                row.push(score.score.map_or(Cell::Empty, Cell::Number));
+                row.push(if score.compensation {
+                    Cell::Text("org.".to_owned())
+                } else {
+                    score.place.map_or(Cell::Empty, Cell::Number)
+                });
+                row.push(Cell::Flag(score.counted));
+            }
+            row.push(Cell::Number(entry.total_score));
+            row
+        })
+        .collect();
+
+    Ok(CategoryTable {
+        age_class: age_class.to_owned(),
+        header,
+        rows,
+        text_columns: 2,
+    })
+}
+
+// Place, name, club, a column per event and the total
+fn summary_table(
+    db: &dyn Database,
+    age_class: &str,
+    ranking: &[RankingEntry],
+    format: ExportFormat,
+) -> anyhow::Result<CategoryTable> {
+    let conn = db.open()?;
+    let mut header: Vec<String> = ["Pl.", "Naam", "Club"].map(String::from).to_vec();
+    if let Some(entry) = ranking.first() {
+        for score in &entry.scores {
+            header.push(event_name(&conn, score.event_id)?);
+        }
+    }
+    header.push("Totaal".to_owned());
+
+    let rows = ranking
+        .iter()
+        .zip(ranking_places(ranking))
+        .map(|(entry, place)| {
+            let mut row = vec![
+                Cell::Text(format!("{}.", place)),
+                Cell::Text(entry.name.clone()),
+                Cell::Text(entry.club.clone()),
+            ];
+            for score in &entry.scores {
+                row.push(match score.score {
+                    Some(points) => {
+                        let place = if score.compensation {
+                            "org.".to_owned()
+                        } else {
+                            score
+                                .place
+                                .map_or("-".to_owned(), |place| format!("{}.", place))
+                        };
+                        let text = format!("{} ({})", points, place);
+                        Cell::Text(match (score.counted, format) {
+                            (true, _) => text,
+                            (false, ExportFormat::Markdown) => format!("~~{}~~", text),
+                            (false, _) => format!("[{}]", text),
+                        })
+                    }
+                    None => Cell::Empty,
+                });
+            }
+            row.push(Cell::Number(entry.total_score));
+            row
+        })
+        .collect();
+
+    Ok(CategoryTable {
+        age_class: age_class.to_owned(),
+        header,
+        rows,
+        text_columns: 2,
+    })
+}
+
+fn cell_text(cell: &Cell) -> String {
+    match cell {
+        Cell::Text(text) => text.clone(),
+        Cell::Number(number) => number.to_string(),
+        Cell::Flag(true) => "x".to_owned(),
+        Cell::Flag(false) | Cell::Empty => String::new(),
+    }
+}
+
+fn write_markdown(tables: &[CategoryTable]) -> String {
+    let mut output = String::new();
+    for table in tables {
+        output.push_str(&format!("## {}\n\n", table.age_class));
+        output.push_str(&format!("| {} |\n", table.header.join(" | ")));
+        let alignments: Vec<&str> = (0..table.header.len())
+            .map(|column| {
+                if (1..=table.text_columns).contains(&column) {
+                    "---"
+                } else {
+                    "--:"
+                }
+            })
+            .collect();
+        output.push_str(&format!("| {} |\n", alignments.join(" | ")));
+        for row in &table.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| cell_text(cell).replace('|', "\\|"))
+                .collect();
+            output.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn write_text(tables: &[CategoryTable]) -> String {
+    let mut output = String::new();
+    for table in tables {
+        let rows: Vec<Vec<String>> = std::iter::once(table.header.clone())
+            .chain(
+                table
+                    .rows
+                    .iter()
+                    .map(|row| row.iter().map(cell_text).collect()),
+            )
+            .collect();
+        let widths: Vec<usize> = (0..table.header.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        output.push_str(&format!("{}\n", table.age_class));
+        for (index, row) in rows.iter().enumerate() {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (text, &width))| {
+                    // Text columns to the left, places and scores to the right
+                    if (1..=table.text_columns).contains(&column) {
+                        format!("{:<width$}", text)
+                    } else {
+                        format!("{:>width$}", text)
+                    }
+                })
+                .collect();
+            output.push_str(line.join("  ").trim_end());
+            output.push('\n');
+            if index == 0 {
+                output.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+                output.push('\n');
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn write_delimited(tables: &[CategoryTable], delimiter: u8) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(vec![]);
+
+    // Categories of a combined cup can have different events
+    let mut previous_header: Option<&[String]> = None;
+    for table in tables {
+        if previous_header != Some(&table.header) {
+            writer.write_record(
+                std::iter::once("Categorie").chain(table.header.iter().map(|s| s.as_str())),
+            )?;
+            previous_header = Some(&table.header);
+        }
+        for row in &table.rows {
+            let record = row.iter().map(cell_text);
+            writer.write_record(std::iter::once(table.age_class.clone()).chain(record))?;
+        }
+    }
+    Ok(writer.into_inner()?)
+}
+
+fn write_workbook(tables: &[CategoryTable]) -> anyhow::Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+
+    for table in tables {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&table.age_class)?;
+        for (column, title) in table.header.iter().enumerate() {
+            worksheet.write_string_with_format(0, column as u16, title, &bold)?;
+        }
+        for (index, row) in table.rows.iter().enumerate() {
+            let row_number = index as u32 + 1;
+            for (column, cell) in row.iter().enumerate() {
+                let column = column as u16;
+                match cell {
+                    Cell::Text(text) => {
+                        worksheet.write_string(row_number, column, text)?;
+                    }
+                    Cell::Number(number) => {
+                        worksheet.write_number(row_number, column, *number)?;
+                    }
+                    Cell::Flag(flag) => {
+                        worksheet.write_boolean(row_number, column, *flag)?;
+                    }
+                    Cell::Empty => {}
+                }
+            }
+        }
+        worksheet.set_freeze_panes(1, 0)?;
+        worksheet.autofit();
+    }
+    Ok(workbook.save_to_buffer()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
+
+    use crate::{
+        db::Database,
+        testing::{add_event, add_result, database},
+        RankingOptions,
+    };
+
+    use super::{export_rankings, write_text, CategoryTable, Cell, ExportFormat};
+
+    const EVENT: &str = "forest-cup 2026-01-17 09:00:00 UTC";
+
+    fn exported(db: &dyn Database, format: ExportFormat) -> Vec<u8> {
+        export_rankings(
+            db,
+            "forest-cup",
+            2026,
+            &["H21".to_owned(), "D21".to_owned()],
+            4,
+            &RankingOptions::default(),
+            format,
+        )
+        .unwrap()
+    }
+
+    fn two_categories() -> impl Database {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        for (name, club, course, age_class, time) in [
+            (
+                "Jan \"Jos\" Janssens, jr.",
+                "Omega",
+                "H:Zwart Extra Lang",
+                "H21",
+                "01:00:00",
+            ),
+            (
+                "Piet Pieters",
+                "K.O.L.",
+                "H:Zwart Extra Lang",
+                "H21",
+                "01:20:00",
+            ),
+            ("Bea | B", "Trol", "D:Zwart Lang", "D21", "01:00:00"),
+        ] {
+            add_result(&db, event_id, name, club, course, age_class, time);
+        }
+        db
+    }
+
+    #[test]
+    fn delimited() {
+        let db = two_categories();
+        for (format, delimiter) in [(ExportFormat::Csv, b','), (ExportFormat::Tsv, b'\t')] {
+            let export = exported(&db, format);
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .from_reader(export.as_slice());
+            let header: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+            assert_eq!(
+                header,
+                vec![
+                    "Categorie".to_owned(),
+                    "Plaats".to_owned(),
+                    "Naam".to_owned(),
+                    "Club".to_owned(),
+                    format!("{} punten", EVENT),
+                    format!("{} plaats", EVENT),
+                    format!("{} telt", EVENT),
+                    "Totaal".to_owned(),
+                ]
+            );
+            let rows: Vec<Vec<String>> = reader
+                .records()
+                .map(|record| record.unwrap().iter().map(String::from).collect())
+                .collect();
+            assert_eq!(
+                rows,
+                [
+                    [
+                        "H21",
+                        "1",
+                        "Jan \"Jos\" Janssens, jr.",
+                        "Omega",
+                        "1000",
+                        "1",
+                        "x",
+                        "1000"
+                    ],
+                    ["H21", "2", "Piet Pieters", "K.O.L.", "750", "2", "x", "750"],
+                    ["D21", "1", "Bea | B", "Trol", "1000", "1", "x", "1000"],
+                ]
+                .map(|row| row.map(String::from).to_vec())
+            );
+        }
+        // Names with the delimiter or quotes are quoted
+        let csv = String::from_utf8(exported(&db, ExportFormat::Csv)).unwrap();
+        assert!(csv.contains(r#"H21,1,"Jan ""Jos"" Janssens, jr.",Omega,"#));
+    }
+
+    #[test]
+    fn markdown() {
+        let db = two_categories();
+        let markdown = String::from_utf8(exported(&db, ExportFormat::Markdown)).unwrap();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[0], "## H21");
+        assert_eq!(
+            lines[2..6],
+            [
+                format!("| Pl. | Naam | Club | {} | Totaal |", EVENT).as_str(),
+                "| --: | --- | --- | --: | --: |",
+                "| 1. | Jan \"Jos\" Janssens, jr. | Omega | 1000 (1.) | 1000 |",
+                "| 2. | Piet Pieters | K.O.L. | 750 (2.) | 750 |",
+            ]
+        );
+        assert!(markdown.contains("| 1. | Bea \\| B | Trol | 1000 (1.) | 1000 |\n"));
+        assert!(markdown.ends_with("Doorstreepte resultaten tellen niet mee.\n"));
+    }
+
+    #[test]
+    fn workbook() {
+        let db = two_categories();
+        let export = exported(&db, ExportFormat::Xlsx);
+        let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(export)).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["H21", "D21"]);
+        let sheet = workbook.worksheet_range("H21").unwrap();
+        assert_eq!(sheet.get_size(), (3, 7));
+        assert_eq!(
+            sheet.get_value((0, 1)),
+            Some(&Data::String("Naam".to_owned()))
+        );
+        assert_eq!(
+            sheet.get_value((1, 1)),
+            Some(&Data::String("Jan \"Jos\" Janssens, jr.".to_owned()))
+        );
+        assert_eq!(sheet.get_value((2, 3)), Some(&Data::Float(750.0)));
+        assert_eq!(sheet.get_value((2, 5)), Some(&Data::Bool(true)));
+    }
+
+    #[test]
+    fn aligned_text() {
+        let table = CategoryTable {
+            age_class: "H21".to_owned(),
+            header: ["Pl.", "Naam", "Club", "Totaal"].map(String::from).to_vec(),
+            rows: vec![
+                vec![
+                    Cell::Text("1.".to_owned()),
+                    Cell::Text("Jan J".to_owned()),
+                    Cell::Text("Omega".to_owned()),
+                    Cell::Number(3000),
+                ],
+                vec![
+                    Cell::Text("10.".to_owned()),
+                    Cell::Text("Bea B".to_owned()),
+                    Cell::Empty,
+                    Cell::Number(880),
+                ],
+            ],
+            text_columns: 2,
+        };
+        assert_eq!(
+            write_text(&[table]),
+            "H21\n\
+             Pl.  Naam   Club   Totaal\n\
+             -------------------------\n\
+             \x201.  Jan J  Omega    3000\n\
+             10.  Bea B            880\n\
+             \n"
+        );
+    }
+}
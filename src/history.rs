@@ -0,0 +1,218 @@
+// Results of a runner across seasons and cups
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::bail;
+use chrono::NaiveTime;
+use itertools::Itertools;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use crate::{db::Database, ranking_places, seasons, snapshots::official_ranking};
+
+#[derive(Debug, Serialize)]
+pub struct HistoryResult {
+    #[serde(rename = "eventId")]
+    pub event_id: i64,
+    pub event: String,
+    pub date: String,
+    pub location: String,
+    pub cup: String,
+    pub season: i16,
+    pub course: String,
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub position: u32,
+    pub time: NaiveTime,
+    pub score: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeasonStanding {
+    pub cup: String,
+    pub season: i16,
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub place: Option<u32>,
+    #[serde(rename = "totalScore")]
+    pub total_score: Option<u32>,
+    /// Why the category could not be ranked, the other seasons are still reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunnerHistory {
+    pub name: String,
+    pub club: String,
+    pub results: Vec<HistoryResult>,
+    pub standings: Vec<SeasonStanding>,
+}
+
+/// Find all stored results of a runner with the scores the rules of each season gave them.
+pub fn runner_history(db: &dyn Database, name: &str) -> anyhow::Result<RunnerHistory> {
+    let conn = db.open()?;
+
+    let club: String = match conn
+        .query_row(
+            "select club from Runner where name = ?",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()?
+    {
+        Some(club) => club,
+        None => bail!("unknown runner {}", name),
+    };
+
+    let mut stmt = conn.prepare(
+        "
+        select
+            Event.id,
+            Event.name,
+            Event.date,
+            Event.location,
+            Event.cup,
+            cast(Event.season as integer),
+            Result.category_name,
+            Result.age_class,
+            Result.position,
+            Result.time
+        from Result join Runner on Result.runner_id = Runner.id
+                    join Event on Result.event_id = Event.id
+        where Runner.name = ?
+        order by Event.date asc
+    ",
+    )?;
+    let mut results: Vec<HistoryResult> = stmt
+        .query_map(params![name], |row| {
+            Ok(HistoryResult {
+                event_id: row.get(0)?,
+                event: row.get(1)?,
+                date: row.get(2)?,
+                location: row.get(3)?,
+                cup: row.get(4)?,
+                season: row.get(5)?,
+                course: row.get(6)?,
+                age_class: row.get(7)?,
+                position: row.get(8)?,
+                time: row.get(9)?,
+                score: None,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+
+    // Rank the runner in the last age class they ran in each season
+    let mut standings = vec![];
+    let seasons: Vec<(String, i16, String)> = results
+        .iter()
+        .map(|result| (result.cup.clone(), result.season))
+        .unique()
+        .map(|(cup, season)| {
+            let age_class = results
+                .iter()
+                .rfind(|result| result.cup == cup && result.season == season)
+                .map(|result| result.age_class.clone())
+                .unwrap_or_default();
+            (cup, season, age_class)
+        })
+        .collect();
+    for (cup, season, age_class) in seasons {
+        let events_count = seasons::events_count(&cup, season);
+        let ranking =
+            match official_ranking(db, cup.clone(), season, age_class.clone(), events_count) {
+                Ok(ranking) => ranking,
+                Err(error) => {
+                    standings.push(SeasonStanding {
+                        cup,
+                        season,
+                        age_class,
+                        place: None,
+                        total_score: None,
+                        error: Some(error.to_string()),
+                    });
+                    continue;
+                }
+            };
+        let places = ranking_places(&ranking);
+        let position = ranking.iter().position(|entry| entry.name == name);
+
+        if let Some(position) = position {
+            for score in &ranking[position].scores {
+                for result in results.iter_mut() {
+                    if result.event_id == score.event_id {
+                        result.score = score.score;
+                    }
+                }
+            }
+        }
+
+        standings.push(SeasonStanding {
+            cup,
+            season,
+            age_class,
+            place: position.map(|position| places[position]),
+            total_score: position.map(|position| ranking[position].total_score),
+            error: None,
+        });
+    }
+
+    Ok(RunnerHistory {
+        name: name.to_owned(),
+        club,
+        results,
+        standings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{add_event, add_result, database};
+
+    use super::runner_history;
+
+    #[test]
+    fn two_seasons_and_a_class_change() {
+        let db = database();
+        let first = add_event(&db, "forest-cup", 2025, "2025-01-18");
+        add_result(&db, first, "A", "Omega", "H:01", "H-20", "01:00:00");
+        add_result(&db, first, "B", "Omega", "H:01", "H-20", "01:10:00");
+        let second = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            second,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        // A category that can't be ranked doesn't hide the other seasons
+        let third = add_event(&db, "city-cup", 2026, "2026-02-14");
+        add_result(&db, third, "A", "Omega", "Sprint", "Sprint", "00:20:00");
+
+        let history = runner_history(&db, "A").unwrap();
+        let scores: Vec<Option<u32>> = history.results.iter().map(|result| result.score).collect();
+        assert_eq!(scores, vec![Some(1000), Some(1000), None]);
+        let standings: Vec<(i16, &str, Option<u32>, bool)> = history
+            .standings
+            .iter()
+            .map(|standing| {
+                (
+                    standing.season,
+                    standing.age_class.as_str(),
+                    standing.place,
+                    standing.error.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            standings,
+            vec![
+                (2025, "H-20", Some(1), false),
+                (2026, "H21", Some(1), false),
+                (2026, "Sprint", None, true),
+            ]
+        );
+    }
+}
@@ -1,24 +1,53 @@
 // SPDX-FileCopyrightText: 2021 Jeroen Hoekx
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use anyhow::bail;
-use chrono::{NaiveTime, Timelike};
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
 use db::Database;
 use indexmap::IndexSet;
+use itertools::Itertools;
 use once_cell::sync::Lazy;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use seasons::{CompensationPolicy, ReferenceCourse, ReferencePolicy, WrongCoursePolicy};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+pub mod api;
+pub mod certificates;
+pub mod class_changes;
 pub mod cli;
+pub mod clinch;
+pub mod clubs;
+pub mod combined;
 pub mod db;
+pub mod diff;
+pub mod discovery;
+pub mod events;
+pub mod explain;
+pub mod export;
+pub mod history;
 pub mod iof;
+pub mod pages;
+pub mod podium;
+pub mod print;
+pub mod progression;
+pub mod publish;
+pub mod registry;
 mod rules_2022;
 mod rules_2023;
 mod rules_2024;
 mod rules_2026;
+pub mod seasons;
+pub mod snapshots;
+#[cfg(test)]
+mod testing;
 pub mod webres;
+pub mod whatif;
 
 const CLUBS: &[&str] = &[
     "Antwerp Orienteers",
@@ -219,12 +248,6 @@ pub struct ResultProcessingOptions {
     pub competitors: Vec<Competitor>,
 }
 
-impl ResultProcessingOptions {
-    pub fn validate_club(&self) -> bool {
-        self.cup == "kampioen"
-    }
-}
-
 pub fn create_database(db: &dyn Database) -> Result<(), anyhow::Error> {
     let conn = db.open()?;
     conn.pragma_update(None, "foreign_keys", "on")?;
@@ -258,12 +281,110 @@ pub fn create_database(db: &dyn Database) -> Result<(), anyhow::Error> {
             age_class text not null,
             position integer not null,
             time text not null,
+            wrong_course integer not null default 0,
+            club text not null default '',
 
             foreign key(event_id) references Event(id),
             foreign key(runner_id) references Runner(id)
-        )
+        );
+
+        create table if not exists Course (
+            id integer primary key autoincrement,
+            event_id integer not null,
+            name text not null,
+            distance integer not null,
+            climb integer not null,
+
+            unique(event_id, name),
+            foreign key(event_id) references Event(id)
+        );
+
+        create table if not exists Organiser (
+            id integer primary key autoincrement,
+            event_id integer not null,
+            runner_id integer not null,
+            role text not null,
+
+            unique(event_id, runner_id),
+            foreign key(event_id) references Event(id),
+            foreign key(runner_id) references Runner(id)
+        );
+
+        create table if not exists ClassChange (
+            id integer primary key autoincrement,
+            cup text not null,
+            season text not null,
+            runner_id integer not null,
+            from_class text not null,
+            age_class text not null,
+            date text not null,
+
+            unique(cup, season, runner_id, date),
+            foreign key(runner_id) references Runner(id)
+        );
+
+        create table if not exists Registration (
+            id integer primary key autoincrement,
+            runner_id integer not null,
+            federation text,
+            nationality text,
+            licence text,
+
+            unique(runner_id),
+            foreign key(runner_id) references Runner(id)
+        );
+
+        create table if not exists Snapshot (
+            id integer primary key autoincrement,
+            cup text not null,
+            season integer not null,
+            age_class text not null,
+            created text not null,
+            data text not null,
+            events_count integer,
+
+            unique(cup, season, age_class)
+        );
     ",
     )?;
+
+    // Databases created before results in a shorter course were kept
+    let has_wrong_course: bool = conn.query_row(
+        "select count(*) > 0 from pragma_table_info('Result') where name = 'wrong_course'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_wrong_course {
+        conn.execute(
+            "alter table Result add column wrong_course integer not null default 0",
+            [],
+        )?;
+    }
+
+    // Snapshots frozen before the number of counted events was stored count the events of their season
+    let has_snapshot_events: bool = conn.query_row(
+        "select count(*) > 0 from pragma_table_info('Snapshot') where name = 'events_count'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_snapshot_events {
+        conn.execute("alter table Snapshot add column events_count integer", [])?;
+    }
+
+    // Databases created before the club of each result was kept take the last club of the runner
+    let has_result_club: bool = conn.query_row(
+        "select count(*) > 0 from pragma_table_info('Result') where name = 'club'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_result_club {
+        conn.execute_batch(
+            "
+            alter table Result add column club text not null default '';
+            update Result set club = (select club from Runner where Runner.id = Result.runner_id);
+        ",
+        )?;
+    }
     Ok(())
 }
 
@@ -276,11 +397,11 @@ pub fn store_event(
 
     let event_db_id = prepare_event(&conn, &options.cup, &options.season, &event)?;
     if options.cup == "kampioen" || (options.results_by_class.unwrap_or(false)) {
-        store_event_by_class(conn, event, options, event_db_id)?;
+        store_event_by_class(&conn, event, options, event_db_id)?;
     } else if &event.name == "Herfstwisselbeker" && &options.season == "2026" {
-        store_oro_hydro_event(conn, event, event_db_id)?;
+        store_oro_hydro_event(&conn, event, event_db_id)?;
     } else {
-        store_event_by_colored_course(conn, event, options, event_db_id)?;
+        store_event_by_colored_course(&conn, event, options, event_db_id)?;
     }
 
     Ok(())
@@ -312,11 +433,26 @@ fn prepare_event(
     ",
         params![event_db_id],
     )?;
+    for category in event.categories.values() {
+        conn.execute(
+            "
+            insert into Course (event_id, name, distance, climb) values (?, ?, ?, ?)
+            on conflict (event_id, name) do update
+            set distance = excluded.distance, climb = excluded.climb
+        ",
+            params![
+                event_db_id,
+                category.name,
+                category.distance,
+                category.climb
+            ],
+        )?;
+    }
     Ok(event_db_id)
 }
 
 fn store_event_by_class(
-    conn: Connection,
+    conn: &Connection,
     event: webres::Event,
     options: &ResultProcessingOptions,
     event_db_id: i64,
@@ -334,11 +470,8 @@ fn store_event_by_class(
                 continue;
             }
 
+            // Runners of other clubs are kept as guests, the eligibility is checked when ranking
             let club = result.club.to_string();
-            if options.validate_club() && !is_ov_club(&club) {
-                eprintln!("Skipping club {}", club);
-                continue;
-            }
 
             conn.execute(
                 "
@@ -376,8 +509,9 @@ fn store_event_by_class(
 
             conn.execute(
                 "
-                insert into Result (event_id, runner_id, category_name, age_class, position, time)
-                values (?, ?, ?, ?, ?, ?)
+                insert into Result
+                    (event_id, runner_id, category_name, age_class, position, time, club)
+                values (?, ?, ?, ?, ?, ?, ?)
             ",
                 params![
                     event_db_id,
@@ -385,7 +519,8 @@ fn store_event_by_class(
                     &category.name,
                     age_class,
                     result.position,
-                    result.time
+                    result.time,
+                    club
                 ],
             )?;
         }
@@ -395,19 +530,19 @@ fn store_event_by_class(
 }
 
 fn is_ov_club(club: &str) -> bool {
-    for existing_club in CLUBS {
-        if club
-            .to_lowercase()
+    normalize_club(club).is_some()
+}
+
+/// Canonical name of an Orienteering Vlaanderen club.
+fn normalize_club(club: &str) -> Option<&'static str> {
+    CLUBS.iter().copied().find(|existing_club| {
+        club.to_lowercase()
             .starts_with(&existing_club.to_lowercase())
-        {
-            return true;
-        }
-    }
-    false
+    })
 }
 
 fn store_oro_hydro_event(
-    conn: Connection,
+    conn: &Connection,
     event: webres::Event,
     event_db_id: i64,
 ) -> Result<(), anyhow::Error> {
@@ -468,29 +603,27 @@ fn store_oro_hydro_event(
                 .get_index_of(result_course_name)
                 .unwrap_or_else(|| panic!("unknown course: {}", result_course_name));
 
-            if result_index < course_index {
+            // Kept for the wrong course policy of the season
+            let wrong_course = result_index < course_index;
+            if wrong_course {
                 eprintln!(
-                    "{} {} is running in incorrect course {}, should run {}",
+                    "Warning: {} {} is running in incorrect course {}, should run {}",
                     result.name,
                     age_class,
                     course_name,
                     COURSES_ORO[age_class.as_str()]
                 );
-                continue;
             }
 
-            let mut club = result.club.to_string();
-            for existing_club in CLUBS {
-                if club
-                    .to_lowercase()
-                    .starts_with(&existing_club.to_lowercase())
-                {
-                    club = existing_club.to_string();
-                }
-            }
+            let club = normalize_club(&result.club).unwrap_or(&result.club);
 
-            // Replace category with real forest cup category
-            let replaced_category = COURSES_COLORS[age_class.as_str()];
+            // Replace category with real forest cup category,
+            // a shorter course keeps the course that was run
+            let replaced_category = if wrong_course {
+                category.name.as_str()
+            } else {
+                COURSES_COLORS[age_class.as_str()]
+            };
 
             conn.execute(
                 "
@@ -509,8 +642,9 @@ fn store_oro_hydro_event(
 
             conn.execute(
                 "
-                insert into Result (event_id, runner_id, category_name, age_class, position, time)
-                values (?, ?, ?, ?, ?, ?)
+                insert into Result
+                    (event_id, runner_id, category_name, age_class, position, time, wrong_course, club)
+                values (?, ?, ?, ?, ?, ?, ?, ?)
             ",
                 params![
                     event_db_id,
@@ -518,7 +652,9 @@ fn store_oro_hydro_event(
                     replaced_category,
                     age_class,
                     result.position,
-                    result.time
+                    result.time,
+                    wrong_course,
+                    club
                 ],
             )?;
         }
@@ -528,7 +664,7 @@ fn store_oro_hydro_event(
 }
 
 fn store_event_by_colored_course(
-    conn: Connection,
+    conn: &Connection,
     event: webres::Event,
     options: &ResultProcessingOptions,
     event_db_id: i64,
@@ -594,23 +730,16 @@ fn store_event_by_colored_course(
                 .get_index_of(result_course_name)
                 .unwrap_or_else(|| panic!("unknown course: {}", result_course_name));
 
-            if result_index < course_index {
+            // Kept for the wrong course policy of the season
+            let wrong_course = result_index < course_index;
+            if wrong_course {
                 eprintln!(
-                    "{} {} is running in incorrect course {}, should run {}",
-                    result.name, age_class, course_name, COURSES_NUMBERED[age_class as &str]
+                    "Warning: {} {} is running in incorrect course {}, should run {}",
+                    result.name, age_class, course_name, COURSES_COLORS[age_class as &str]
                 );
-                continue;
             }
 
-            let mut club = result.club.to_string();
-            for existing_club in CLUBS {
-                if club
-                    .to_lowercase()
-                    .starts_with(&existing_club.to_lowercase())
-                {
-                    club = existing_club.to_string();
-                }
-            }
+            let club = normalize_club(&result.club).unwrap_or(&result.club);
 
             conn.execute(
                 "
@@ -629,8 +758,9 @@ fn store_event_by_colored_course(
 
             conn.execute(
                 "
-                insert into Result (event_id, runner_id, category_name, age_class, position, time)
-                values (?, ?, ?, ?, ?, ?)
+                insert into Result
+                    (event_id, runner_id, category_name, age_class, position, time, wrong_course, club)
+                values (?, ?, ?, ?, ?, ?, ?, ?)
             ",
                 params![
                     event_db_id,
@@ -638,7 +768,9 @@ fn store_event_by_colored_course(
                     category.name,
                     age_class,
                     result.position,
-                    result.time
+                    result.time,
+                    wrong_course,
+                    club
                 ],
             )?;
         }
@@ -661,6 +793,389 @@ fn override_age_class(overrides: &[AgeClassOverride], name: &str, age_class: &st
     age_class.to_string()
 }
 
+/// Club member who organised an event instead of running it.
+#[derive(Debug, Serialize)]
+pub struct Organiser {
+    #[serde(rename = "eventId")]
+    pub event_id: i64,
+    pub name: String,
+    pub club: String,
+    pub role: String,
+}
+
+pub fn store_organiser(
+    db: &dyn Database,
+    event_id: i64,
+    name: &str,
+    club: &str,
+    role: &str,
+) -> anyhow::Result<()> {
+    let conn = db.open()?;
+    let exists: bool = conn.query_row(
+        "select count(*) > 0 from Event where id = ?",
+        params![event_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        bail!("Event {} does not exist", event_id);
+    }
+    conn.execute(
+        "
+        insert into Runner (name, club) values (?, ?)
+        on conflict (name) do nothing
+    ",
+        params![name, club],
+    )?;
+    let runner_db_id: i64 = conn.query_row(
+        "
+        select id from Runner where name = ?
+    ",
+        params![name],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "
+        insert into Organiser (event_id, runner_id, role) values (?, ?, ?)
+        on conflict (event_id, runner_id) do update set role = excluded.role
+    ",
+        params![event_id, runner_db_id, role],
+    )?;
+    Ok(())
+}
+
+pub fn remove_organiser(db: &dyn Database, event_id: i64, name: &str) -> anyhow::Result<()> {
+    let conn = db.open()?;
+    let removed = conn.execute(
+        "
+        delete from Organiser
+        where event_id = ?
+          and runner_id = (select id from Runner where name = ?)
+    ",
+        params![event_id, name],
+    )?;
+    if removed == 0 {
+        bail!("{} is not an organiser of event {}", name, event_id);
+    }
+    Ok(())
+}
+
+pub fn list_organisers(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<Vec<Organiser>> {
+    let conn = db.open()?;
+    let mut stmt = conn.prepare(
+        "
+        select Organiser.event_id, Runner.name, Runner.club, Organiser.role
+        from Organiser join Runner on Organiser.runner_id = Runner.id
+                       join Event on Organiser.event_id = Event.id
+        where Event.cup = ? and Event.season = ?
+        order by Event.date asc, Runner.name asc
+    ",
+    )?;
+    let organisers = stmt
+        .query_map(params![cup, season], |row| {
+            Ok(Organiser {
+                event_id: row.get(0)?,
+                name: row.get(1)?,
+                club: row.get(2)?,
+                role: row.get(3)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(organisers)
+}
+
+/// Age classes with results in a season.
+pub(crate) fn find_age_classes(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "
+        select distinct Result.age_class
+        from Result join Event on Result.event_id = Event.id
+        where Event.cup = ? and Event.season = ?
+        order by Result.age_class asc
+    ",
+    )?;
+    let age_classes = stmt
+        .query_map(params![cup, season], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(age_classes)
+}
+
+/// Events organised by each runner in a season, in chronological order.
+fn find_organised_events(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<HashMap<String, Vec<i64>>> {
+    let mut stmt = conn.prepare(
+        "
+        select Runner.name, Organiser.event_id
+        from Organiser join Runner on Organiser.runner_id = Runner.id
+                       join Event on Organiser.event_id = Event.id
+        where Event.cup = ? and Event.season = ?
+        order by Event.date asc
+    ",
+    )?;
+    let mut organised_events: HashMap<String, Vec<i64>> = HashMap::new();
+    for row in stmt.query_map(params![cup, season], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })? {
+        let (name, event_id) = row?;
+        organised_events.entry(name).or_default().push(event_id);
+    }
+    Ok(organised_events)
+}
+
+/// Reference time of every course of the performances, in seconds.
+///
+/// The fastest time of the runners the reference policy of the season declares,
+/// but never slower than an eligible performance, so no ranked score exceeds 1000.
+/// Guests only set it when the policy takes other finishers of the course, or without eligible runners.
+/// A course the season scores against a reference course takes its fastest finisher too.
+fn reference_times(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    performances: &[Performance],
+    context: &RankingContext,
+) -> anyhow::Result<HashMap<(i64, String), u32>> {
+    let fastest_times = |performances: Vec<&Performance>| {
+        let mut fastest_times: HashMap<(i64, String), u32> = HashMap::new();
+        for performance in performances {
+            let seconds = total_seconds(performance.time);
+            fastest_times
+                .entry((performance.event_id, performance.category_name.clone()))
+                .and_modify(|fastest| *fastest = (*fastest).min(seconds))
+                .or_insert(seconds);
+        }
+        fastest_times
+    };
+    // Guests only set the reference of a course without eligible runners
+    let (guests, eligible): (Vec<&Performance>, Vec<&Performance>) = performances
+        .iter()
+        .partition(|performance| context.guests.contains(&performance.name));
+    let mut reference_times = fastest_times(eligible);
+    for (course, fastest) in fastest_times(guests) {
+        reference_times.entry(course).or_insert(fastest);
+    }
+
+    let policy = context
+        .reference
+        .unwrap_or_else(|| seasons::reference_policy(cup, season));
+    let gender = age_class.get(..1);
+    let mut stmt = conn.prepare(
+        "
+        select Runner.name, Result.time
+        from Result join Runner on Result.runner_id = Runner.id
+        where Result.event_id = ? and Result.category_name = ?
+          and not Result.wrong_course
+          and (? is null or substr(Result.age_class, 1, 1) = ?)
+    ",
+    )?;
+    let reference_courses = context.reference_courses(cup, season, age_class);
+    for ((event_id, course), reference_time) in reference_times.iter_mut() {
+        // The course, the gender of its finishers and whether guests count
+        let mut courses = match policy {
+            ReferencePolicy::EligibleRunners => vec![],
+            ReferencePolicy::EligibleFinishers => vec![(course.as_str(), None, false)],
+            ReferencePolicy::AllFinishers => vec![(course.as_str(), None, true)],
+            ReferencePolicy::SameGender => vec![(course.as_str(), gender, true)],
+        };
+        courses.extend(
+            reference_courses
+                .iter()
+                .filter(|reference_course| reference_course.course == *course)
+                .map(|reference_course| (reference_course.reference_course.as_ref(), None, true)),
+        );
+        for (course, gender, with_guests) in courses {
+            for finisher in stmt.query_map(params![event_id, course, gender, gender], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, NaiveTime>(1)?))
+            })? {
+                let (name, time) = finisher?;
+                if with_guests || !context.guests.contains(&name) {
+                    *reference_time = (*reference_time).min(total_seconds(time));
+                }
+            }
+        }
+    }
+    Ok(reference_times)
+}
+
+/// Results of a category in the courses the season scores against a reference course,
+/// besides the course of the category.
+fn reference_course_performances(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    course: &str,
+    context: &mut RankingContext,
+) -> anyhow::Result<Vec<Performance>> {
+    let mut stmt = conn.prepare(
+        "
+        select
+            Runner.name,
+            Result.club,
+            Event.id,
+            Result.age_class,
+            Result.category_name,
+            Result.position,
+            Result.time
+        from Result join Runner on Result.runner_id = Runner.id
+                    join Event on Result.event_id = Event.id
+        where Event.cup = ? and Event.season = ?
+          and Result.category_name = ?
+          and substr(Result.age_class, 1, 1) = substr(?, 1, 1)
+          and not Result.wrong_course
+        order by Runner.name asc, Event.date asc
+    ",
+    )?;
+    let mut performances = vec![];
+    for reference_course in context.reference_courses(cup, season, age_class) {
+        if reference_course.course == course {
+            continue;
+        }
+        for performance in stmt.query_map(
+            params![cup, season, reference_course.course, age_class],
+            |row| {
+                Ok(Performance {
+                    name: row.get(0)?,
+                    club: row.get(1)?,
+                    event_id: row.get(2)?,
+                    age_class: row.get(3)?,
+                    category_name: row.get(4)?,
+                    position: row.get(5)?,
+                    time: row.get(6)?,
+                    score: 0,
+                })
+            },
+        )? {
+            let performance = performance?;
+            context.explain_filter(age_class, &performance, false);
+            performances.push(performance);
+        }
+    }
+    Ok(performances)
+}
+
+/// Scored results of a category in a shorter course than the category should run.
+///
+/// The score is relative to the fastest time of the course that was run,
+/// the wrong course policy of the season decides what remains of it.
+fn wrong_course_performances(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    context: &mut RankingContext,
+) -> anyhow::Result<Vec<Performance>> {
+    let policy = context
+        .wrong_course
+        .unwrap_or_else(|| seasons::wrong_course_policy(cup, season));
+    if policy == WrongCoursePolicy::Exclude {
+        return Ok(vec![]);
+    }
+
+    let mut stmt = conn.prepare(
+        "
+        select
+            Runner.name,
+            Result.club,
+            Event.id,
+            Result.age_class,
+            Result.category_name,
+            Result.position,
+            Result.time
+        from Result join Runner on Result.runner_id = Runner.id
+                    join Event on Result.event_id = Event.id
+        where Event.cup = ? and Event.season = ?
+          and Result.age_class = ?
+          and Result.wrong_course
+          and julianday(Event.date) <= julianday(coalesce(?, Event.date))
+        order by Runner.name asc, Event.date asc
+    ",
+    )?;
+    let mut fastest_times = HashMap::new();
+    let mut performances = vec![];
+    for performance in stmt.query_map(params![cup, season, age_class, context.until], |row| {
+        Ok(Performance {
+            name: row.get(0)?,
+            club: row.get(1)?,
+            event_id: row.get(2)?,
+            age_class: row.get(3)?,
+            category_name: row.get(4)?,
+            position: row.get(5)?,
+            time: row.get(6)?,
+            score: 0,
+        })
+    })? {
+        let performance = performance?;
+        let key = (performance.event_id, performance.category_name.clone());
+        let fastest = match fastest_times.get(&key) {
+            Some(&fastest) => fastest,
+            None => {
+                let fastest =
+                    fastest_time_of_course(conn, performance.event_id, &performance.category_name)?
+                        .map_or(total_seconds(performance.time), total_seconds);
+                fastest_times.insert(key, fastest);
+                fastest
+            }
+        };
+        if let Some(score) = policy.apply(1000 * fastest / total_seconds(performance.time)) {
+            performances.push(Performance {
+                score,
+                ..performance
+            });
+        }
+    }
+    context.explain_scores(age_class, &fastest_times, &performances);
+    for performance in &performances {
+        context.explain_wrong_course(performance);
+    }
+    Ok(performances)
+}
+
+/// Fastest time of a course that was run at an event.
+///
+/// The classes of an Oro-Hydro course are stored in their colored course,
+/// their results are part of the Oro-Hydro course they ran.
+fn fastest_time_of_course(
+    conn: &Connection,
+    event_id: i64,
+    course: &str,
+) -> anyhow::Result<Option<NaiveTime>> {
+    let mut fastest: Option<NaiveTime> = conn.query_row(
+        "select min(time) from Result where event_id = ? and category_name = ?",
+        params![event_id, course],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "
+        select min(time) from Result
+        where event_id = ? and age_class = ? and category_name = ?
+          and not wrong_course
+    ",
+    )?;
+    for (age_class, oro_course) in COURSES_ORO.iter() {
+        if *oro_course != course {
+            continue;
+        }
+        let class_fastest: Option<NaiveTime> = stmt.query_row(
+            params![event_id, age_class, COURSES_COLORS[age_class]],
+            |row| row.get(0),
+        )?;
+        fastest = fastest.into_iter().chain(class_fastest).min();
+    }
+    Ok(fastest)
+}
+
 #[derive(Debug)]
 struct Performance {
     name: String,
@@ -677,15 +1192,31 @@ fn total_seconds(time: impl Timelike) -> u32 {
     time.second() + time.minute() * 60 + time.hour() * 60 * 60
 }
 
-#[derive(Clone, Copy, Debug, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RankingScore {
     #[serde(rename = "eventId")]
     event_id: i64,
     score: Option<u32>,
     place: Option<u32>,
+    /// Points awarded for organising the event instead of running it.
+    compensation: bool,
+    /// The score is one of the best scores that make up the total.
+    counted: bool,
 }
 
-#[derive(Debug, Serialize)]
+impl RankingScore {
+    fn missing(event_id: i64) -> Self {
+        RankingScore {
+            event_id,
+            score: None,
+            place: None,
+            compensation: false,
+            counted: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RankingEntry {
     name: String,
     club: String,
@@ -694,6 +1225,198 @@ pub struct RankingEntry {
     scores: Vec<RankingScore>,
 }
 
+impl RankingEntry {
+    /// Scores organised events the runner did not run, as the compensation policy declares.
+    fn compensate(
+        &mut self,
+        organised_events: &[i64],
+        policy: CompensationPolicy,
+        events_count: usize,
+    ) {
+        let scores: Vec<u32> = self
+            .scores
+            .iter()
+            .filter(|score| !score.compensation)
+            .flat_map(|score| score.score)
+            .collect();
+        let Some(compensation) = policy.score(&scores, events_count) else {
+            return;
+        };
+        let compensated_events: Vec<i64> = organised_events
+            .iter()
+            .copied()
+            .filter(|&event_id| {
+                self.scores
+                    .iter()
+                    .any(|score| score.event_id == event_id && score.score.is_none())
+            })
+            .take(policy.events())
+            .collect();
+        for score in &mut self.scores {
+            if compensated_events.contains(&score.event_id) {
+                score.score = Some(compensation);
+                score.compensation = true;
+            }
+        }
+        self.total_score = self
+            .scores
+            .iter()
+            .flat_map(|score| score.score)
+            .sorted_unstable()
+            .rev()
+            .take(events_count)
+            .sum();
+    }
+
+    // The best `events_count` scores make up the total score
+    fn mark_counted(&mut self, events_count: usize) {
+        let counted: Vec<usize> = self
+            .scores
+            .iter()
+            .enumerate()
+            .filter(|(_, score)| score.score.is_some_and(|score| score > 0))
+            .sorted_by_key(|(_, score)| std::cmp::Reverse(score.score))
+            .take(events_count)
+            .map(|(index, _)| index)
+            .collect();
+        for (index, score) in self.scores.iter_mut().enumerate() {
+            score.counted = counted.contains(&index);
+        }
+    }
+}
+
+/// Places in a ranking sorted by total score, runners with the same total share a place.
+pub fn ranking_places(ranking: &[RankingEntry]) -> Vec<u32> {
+    let mut places: Vec<u32> = Vec::with_capacity(ranking.len());
+    for (index, entry) in ranking.iter().enumerate() {
+        if index > 0 && ranking[index - 1].total_score == entry.total_score {
+            places.push(places[index - 1]);
+        } else {
+            places.push(index as u32 + 1);
+        }
+    }
+    places
+}
+
+/// Version of the ranking rules, named after the season they were introduced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleVersion {
+    Rules2022,
+    Rules2023,
+    Rules2024,
+    Rules2026,
+}
+
+impl std::fmt::Display for RuleVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = match self {
+            RuleVersion::Rules2022 => "2022",
+            RuleVersion::Rules2023 => "2023",
+            RuleVersion::Rules2024 => "2024",
+            RuleVersion::Rules2026 => "2026",
+        };
+        write!(f, "{}", version)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown rules, valid rules are: 2022, 2023, 2024, 2026")]
+pub struct UnknownRuleVersion;
+
+impl FromStr for RuleVersion {
+    type Err = UnknownRuleVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2022" => Ok(RuleVersion::Rules2022),
+            "2023" => Ok(RuleVersion::Rules2023),
+            "2024" => Ok(RuleVersion::Rules2024),
+            "2026" => Ok(RuleVersion::Rules2026),
+            _ => Err(UnknownRuleVersion),
+        }
+    }
+}
+
+/// The rules a season of a cup is ranked with.
+pub fn rule_version(cup: &str, season: i16) -> RuleVersion {
+    if cup == "kampioen" || season < 2023 || (cup == "forest-cup" && season == 2023) {
+        RuleVersion::Rules2022
+    } else if season < 2024 || (cup == "forest-cup" && season == 2024) {
+        RuleVersion::Rules2023
+    } else if season < 2026 {
+        RuleVersion::Rules2024
+    } else {
+        RuleVersion::Rules2026
+    }
+}
+
+/// The moment in a season a ranking is calculated at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsOf {
+    /// Right after an event, events on the same day are included as well.
+    Event(i64),
+    /// At the end of a day.
+    Date(NaiveDate),
+}
+
+#[derive(Error, Debug)]
+#[error("Expected an event id or a date (YYYY-MM-DD)")]
+pub struct InvalidAsOf;
+
+impl FromStr for AsOf {
+    type Err = InvalidAsOf;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            Ok(AsOf::Date(date))
+        } else if let Ok(event_id) = s.parse() {
+            Ok(AsOf::Event(event_id))
+        } else {
+            Err(InvalidAsOf)
+        }
+    }
+}
+
+impl AsOf {
+    /// Events on or before this moment are part of the ranking.
+    fn until(&self, db: &dyn Database) -> anyhow::Result<DateTime<Utc>> {
+        let date = match self {
+            AsOf::Event(event_id) => {
+                let conn = db.open()?;
+                let date: Option<DateTime<Utc>> = conn
+                    .query_row(
+                        "select date from Event where id = ?",
+                        params![event_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                match date {
+                    Some(date) => date.date_naive(),
+                    None => bail!("Event {} does not exist", event_id),
+                }
+            }
+            AsOf::Date(date) => *date,
+        };
+        // The end of the day, so later events on the same day are included
+        Ok(date.and_hms_opt(23, 59, 59).unwrap().and_utc())
+    }
+}
+
+/// Changes to how a ranking is calculated, the defaults give the official ranking.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RankingOptions {
+    /// Only rank the events up to this moment.
+    pub as_of: Option<AsOf>,
+    /// Rules to use instead of the rules of the season.
+    pub rules: Option<RuleVersion>,
+    /// Wrong course policy to use instead of the policy of the season.
+    pub wrong_course: Option<WrongCoursePolicy>,
+    /// Reference policy to use instead of the policy of the season.
+    pub reference: Option<ReferencePolicy>,
+    /// Reference courses to use instead of those of the season.
+    pub reference_courses: Option<Vec<ReferenceCourse>>,
+}
+
 pub fn calculate_ranking(
     db: &dyn Database,
     cup: String,
@@ -701,13 +1424,500 @@ pub fn calculate_ranking(
     age_class: String,
     events_count: usize,
 ) -> Result<Vec<RankingEntry>, anyhow::Error> {
-    if cup == "kampioen" || season < 2023 || (cup == "forest-cup" && season == 2023) {
-        rules_2022::calculate_ranking(db, cup, season, age_class, events_count)
-    } else if season < 2024 || (cup == "forest-cup" && season == 2024) {
-        rules_2023::calculate_ranking(db, cup, season, age_class, events_count)
-    } else if season < 2026 {
-        rules_2024::calculate_ranking(db, cup, season, age_class, events_count)
-    } else {
-        rules_2026::calculate_ranking(db, cup, season, age_class, events_count)
+    calculate_ranking_with(
+        db,
+        cup,
+        season,
+        age_class,
+        events_count,
+        &RankingOptions::default(),
+    )
+}
+
+pub fn calculate_ranking_with(
+    db: &dyn Database,
+    cup: String,
+    season: i16,
+    age_class: String,
+    events_count: usize,
+    options: &RankingOptions,
+) -> Result<Vec<RankingEntry>, anyhow::Error> {
+    if let Some(combined_cup) = combined::find_combined_cup(&cup, season) {
+        return combined::calculate_combined_ranking(db, combined_cup, age_class, options);
+    }
+
+    let rules = options.rules.unwrap_or_else(|| rule_version(&cup, season));
+    let mut context = RankingContext::new(db, options)?;
+    rank(
+        db,
+        cup,
+        season,
+        age_class,
+        events_count,
+        rules,
+        &mut context,
+    )
+}
+
+/// State shared by the rules modules while calculating a ranking.
+pub(crate) struct RankingContext {
+    /// Only events up to this moment count.
+    until: Option<DateTime<Utc>>,
+    /// Wrong course policy to use instead of the policy of the season.
+    wrong_course: Option<WrongCoursePolicy>,
+    /// Reference policy to use instead of the policy of the season.
+    reference: Option<ReferencePolicy>,
+    /// Reference courses to use instead of those of the season.
+    reference_courses: Option<Vec<ReferenceCourse>>,
+    /// Runners who don't meet the eligibility of the season, they don't get a place.
+    guests: HashSet<String>,
+    /// Collects how the scores were derived when explaining a ranking.
+    explanation: Option<explain::Explanation>,
+}
+
+impl RankingContext {
+    fn new(db: &dyn Database, options: &RankingOptions) -> anyhow::Result<Self> {
+        let until = match options.as_of {
+            Some(as_of) => Some(as_of.until(db)?),
+            None => None,
+        };
+        Ok(RankingContext {
+            until,
+            wrong_course: options.wrong_course,
+            reference: options.reference,
+            reference_courses: options.reference_courses.clone(),
+            guests: HashSet::new(),
+            explanation: None,
+        })
+    }
+
+    /// The reference courses of a category.
+    fn reference_courses(&self, cup: &str, season: i16, age_class: &str) -> Vec<ReferenceCourse> {
+        self.reference_courses
+            .as_deref()
+            .unwrap_or_else(|| seasons::reference_courses(cup, season))
+            .iter()
+            .filter(|reference_course| reference_course.age_class == age_class)
+            .cloned()
+            .collect()
+    }
+
+    fn explain_filter(&mut self, ranked_as: &str, performance: &Performance, ignored: bool) {
+        if let Some(explanation) = &mut self.explanation {
+            explanation.filter(ranked_as, performance, ignored);
+        }
+    }
+
+    fn explain_scores(
+        &mut self,
+        ranked_as: &str,
+        fastest_times: &HashMap<(i64, String), u32>,
+        performances: &[Performance],
+    ) {
+        if let Some(explanation) = &mut self.explanation {
+            explanation.scores(ranked_as, fastest_times, performances);
+        }
+    }
+
+    fn explain_class_change(&mut self, performance: &Performance) {
+        if let Some(explanation) = &mut self.explanation {
+            explanation.class_change(performance);
+        }
+    }
+
+    fn explain_wrong_course(&mut self, performance: &Performance) {
+        if let Some(explanation) = &mut self.explanation {
+            explanation.wrong_course(performance);
+        }
+    }
+}
+
+fn rank(
+    db: &dyn Database,
+    cup: String,
+    season: i16,
+    age_class: String,
+    events_count: usize,
+    rules: RuleVersion,
+    context: &mut RankingContext,
+) -> Result<Vec<RankingEntry>, anyhow::Error> {
+    let conn = db.open()?;
+    context.guests =
+        registry::find_guests(&conn, &cup, season, &seasons::eligibility(&cup, season))?;
+    let compensation_policy = seasons::compensation_policy(&cup, season);
+    let organised_events = find_organised_events(&conn, &cup, season)?;
+    let mut ranking = match rules {
+        RuleVersion::Rules2022 => {
+            rules_2022::calculate_ranking(db, cup, season, age_class, events_count, context)
+        }
+        RuleVersion::Rules2023 => {
+            rules_2023::calculate_ranking(db, cup, season, age_class, events_count, context)
+        }
+        RuleVersion::Rules2024 => {
+            rules_2024::calculate_ranking(db, cup, season, age_class, events_count, context)
+        }
+        RuleVersion::Rules2026 => {
+            rules_2026::calculate_ranking(db, cup, season, age_class, events_count, context)
+        }
+    }?;
+    // Guests may set reference times, but are not ranked
+    ranking.retain(|entry| !context.guests.contains(&entry.name));
+    // Organisers are compensated the same way for every rule version, only runners with a
+    // result in the category are ranked, so organisers without one are not compensated.
+    if compensation_policy != CompensationPolicy::None {
+        for entry in &mut ranking {
+            if let Some(events) = organised_events.get(&entry.name) {
+                entry.compensate(events, compensation_policy, events_count);
+            }
+        }
+        ranking.sort_by_key(|entry| std::cmp::Reverse(entry.total_score));
+    }
+    for entry in &mut ranking {
+        entry.mark_counted(events_count);
+    }
+    Ok(ranking)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+    use rusqlite::params;
+
+    use crate::{
+        db::Database,
+        testing::{add_event, add_result, database, entry},
+    };
+
+    use super::{
+        calculate_ranking, calculate_ranking_with, list_organisers, ranking_places,
+        reference_times,
+        seasons::{ReferencePolicy, WrongCoursePolicy},
+        store_organiser, AsOf, Performance, RankingContext, RankingEntry, RankingOptions,
+        RankingScore, RuleVersion,
+    };
+
+    fn wrong_course_ranking(db: &dyn Database, policy: WrongCoursePolicy) -> Vec<RankingEntry> {
+        let options = RankingOptions {
+            wrong_course: Some(policy),
+            ..Default::default()
+        };
+        calculate_ranking_with(
+            db,
+            "forest-cup".to_owned(),
+            2026,
+            "H21".to_owned(),
+            4,
+            &options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn places_with_ties() {
+        let ranking = vec![
+            entry("", 3000),
+            entry("", 2900),
+            entry("", 2900),
+            entry("", 2800),
+        ];
+        assert_eq!(ranking_places(&ranking), vec![1, 2, 2, 4]);
+    }
+
+    #[test]
+    fn counted_scores() {
+        let mut entry = entry("", 0);
+        entry.scores = [Some(900), None, Some(950), Some(900), Some(0)]
+            .into_iter()
+            .enumerate()
+            .map(|(event_id, score)| RankingScore {
+                score,
+                ..RankingScore::missing(event_id as i64)
+            })
+            .collect();
+        entry.mark_counted(2);
+        let counted: Vec<bool> = entry.scores.iter().map(|score| score.counted).collect();
+        assert_eq!(counted, vec![true, false, true, false, false]);
+    }
+
+    #[test]
+    fn organiser_of_missing_event() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        assert!(
+            store_organiser(&db, event_id + 1, "Jan Janssens", "Omega", "course builder").is_err()
+        );
+        store_organiser(&db, event_id, "Jan Janssens", "Omega", "course builder").unwrap();
+        let organisers = list_organisers(&db, "forest-cup", 2026).unwrap();
+        assert_eq!(organisers.len(), 1);
+        assert_eq!(organisers[0].event_id, event_id);
+    }
+
+    #[test]
+    fn compensated_with_every_rule_version() {
+        let db = database();
+        let events: Vec<i64> = ["2026-01-17", "2026-02-14"]
+            .iter()
+            .map(|date| add_event(&db, "forest-cup", 2026, date))
+            .collect();
+        for &event_id in &events {
+            add_result(&db, event_id, "A", "Omega", "H:01", "H21", "01:00:00");
+        }
+        add_result(&db, events[0], "B", "hamok", "H:01", "H21", "01:20:00");
+        store_organiser(&db, events[1], "B", "hamok", "course builder").unwrap();
+        // Organisers without a result in the category are not ranked
+        store_organiser(&db, events[1], "C", "Trol", "course builder").unwrap();
+
+        let options = RankingOptions {
+            rules: Some(RuleVersion::Rules2024),
+            ..Default::default()
+        };
+        let ranking = calculate_ranking_with(
+            &db,
+            "forest-cup".to_owned(),
+            2026,
+            "H21".to_owned(),
+            4,
+            &options,
+        )
+        .unwrap();
+        let names: Vec<&str> = ranking.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "B"]);
+        assert!(ranking[1].scores[1].compensation);
+        assert_eq!(ranking[1].scores[1].score, Some(750));
+        assert_eq!(ranking[1].total_score, 1500);
+    }
+
+    #[test]
+    fn as_of_event_on_a_day_with_more_events() {
+        let db = database();
+        let afternoon = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        db.open()
+            .unwrap()
+            .execute(
+                "update Event set date = replace(date, '09:00', '14:00') where id = ?",
+                params![afternoon],
+            )
+            .unwrap();
+        let morning = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        let next_day = add_event(&db, "forest-cup", 2026, "2026-01-18");
+        for event_id in [morning, afternoon, next_day] {
+            add_result(
+                &db,
+                event_id,
+                "A",
+                "Omega",
+                "H:Zwart Extra Lang",
+                "H21",
+                "01:00:00",
+            );
+        }
+
+        let options = RankingOptions {
+            as_of: Some(AsOf::Event(morning)),
+            ..Default::default()
+        };
+        let ranking = calculate_ranking_with(
+            &db,
+            "forest-cup".to_owned(),
+            2026,
+            "H21".to_owned(),
+            4,
+            &options,
+        )
+        .unwrap();
+        let events: Vec<i64> = ranking[0]
+            .scores
+            .iter()
+            .map(|score| score.event_id)
+            .collect();
+        assert_eq!(events, vec![morning, afternoon]);
+        assert_eq!(ranking[0].total_score, 2000);
+    }
+
+    #[test]
+    fn wrong_course_policies() {
+        let db = database();
+        let event = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        add_result(
+            &db,
+            event,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        // A should run Zwart Extra Lang, the fastest of Zwart Lang is the reference
+        add_result(&db, event, "A", "Omega", "H:Zwart Lang", "H21", "00:40:00");
+        add_result(&db, event, "C", "Omega", "H:Zwart Lang", "H45", "00:32:00");
+        db.open()
+            .unwrap()
+            .execute(
+                "
+                update Result set wrong_course = 1
+                where runner_id = (select id from Runner where name = 'A')
+            ",
+                [],
+            )
+            .unwrap();
+
+        let totals = |policy| {
+            wrong_course_ranking(&db, policy)
+                .into_iter()
+                .map(|entry| (entry.name, entry.total_score))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            totals(WrongCoursePolicy::Exclude),
+            vec![("B".to_owned(), 1000)]
+        );
+        assert_eq!(
+            totals(WrongCoursePolicy::ScoreZero),
+            vec![("B".to_owned(), 1000), ("A".to_owned(), 0)]
+        );
+        assert_eq!(
+            totals(WrongCoursePolicy::Penalty(50)),
+            vec![("B".to_owned(), 1000), ("A".to_owned(), 400)]
+        );
+        assert_eq!(
+            totals(WrongCoursePolicy::Allow),
+            vec![("B".to_owned(), 1000), ("A".to_owned(), 800)]
+        );
+    }
+
+    #[test]
+    fn wrong_oro_hydro_course() {
+        // Results of an Oro-Hydro event are stored in the colored course of their class
+        let db = database();
+        let event = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        add_result(
+            &db,
+            event,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(&db, event, "A", "Omega", "H:2 Oro-hydro", "H21", "00:40:00");
+        add_result(&db, event, "C", "Omega", "H:Zwart Lang", "H45", "00:32:00");
+        // Course 4 is shorter, H-14 did not run course 2
+        add_result(
+            &db,
+            event,
+            "D",
+            "Omega",
+            "H:Rood Midden",
+            "H-14",
+            "00:20:00",
+        );
+        db.open()
+            .unwrap()
+            .execute(
+                "update Result set wrong_course = 1 where category_name = 'H:2 Oro-hydro'",
+                [],
+            )
+            .unwrap();
+
+        let ranking = wrong_course_ranking(&db, WrongCoursePolicy::Allow);
+        assert_eq!(ranking[1].name, "A");
+        assert_eq!(ranking[1].total_score, 800);
+    }
+
+    #[test]
+    fn reference_time_policies() {
+        let db = database();
+        let event = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        add_result(
+            &db,
+            event,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            event,
+            "Y",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H35",
+            "00:50:00",
+        );
+        add_result(
+            &db,
+            event,
+            "X",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "D21",
+            "00:40:00",
+        );
+        add_result(&db, event, "Z", "Omega", "H:Zwart Lang", "H45", "00:30:00");
+        // Only A is ranked in the category
+        let performances = vec![Performance {
+            name: "A".to_owned(),
+            club: "Omega".to_owned(),
+            event_id: event,
+            age_class: "H21".to_owned(),
+            category_name: "H:Zwart Extra Lang".to_owned(),
+            position: 3,
+            time: NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+            score: 0,
+        }];
+
+        let reference_time = |options: RankingOptions| {
+            let context = RankingContext::new(&db, &options).unwrap();
+            let conn = db.open().unwrap();
+            let times =
+                reference_times(&conn, "forest-cup", 2026, "H21", &performances, &context).unwrap();
+            times[&(event, "H:Zwart Extra Lang".to_owned())]
+        };
+        let with_policy = |policy| RankingOptions {
+            reference: Some(policy),
+            ..Default::default()
+        };
+        assert_eq!(
+            reference_time(with_policy(ReferencePolicy::EligibleRunners)),
+            3600
+        );
+        assert_eq!(
+            reference_time(with_policy(ReferencePolicy::AllFinishers)),
+            2400
+        );
+        assert_eq!(
+            reference_time(with_policy(ReferencePolicy::SameGender)),
+            3000
+        );
+        assert_eq!(
+            reference_time(RankingOptions {
+                reference: Some(ReferencePolicy::EligibleRunners),
+                reference_courses: Some(vec!["H21:H:Zwart Extra Lang=H:Zwart Lang"
+                    .parse()
+                    .unwrap()]),
+                ..Default::default()
+            }),
+            1800
+        );
+    }
+
+    #[test]
+    fn women_in_course_1() {
+        // The 2024 rules score the women of course 1 against the men
+        let db = database();
+        let event = add_event(&db, "city-cup", 2025, "2025-03-15");
+        add_result(&db, event, "A", "Omega", "D:02", "D21", "01:00:00");
+        add_result(&db, event, "B", "Omega", "D:01", "D21", "01:10:00");
+        add_result(&db, event, "M", "Omega", "H:01", "H21", "00:56:00");
+
+        let ranking =
+            calculate_ranking(&db, "city-cup".to_owned(), 2025, "D21".to_owned(), 4).unwrap();
+        let totals: Vec<(&str, u32)> = ranking
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.total_score))
+            .collect();
+        assert_eq!(totals, vec![("A", 1000), ("B", 800)]);
     }
 }
@@ -0,0 +1,395 @@
+// Season pages rendered from the database and templates
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+use minijinja::{context, path_loader, Environment};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::Database,
+    discovery::{list_categories, list_events, list_seasons},
+    seasons,
+};
+
+const MONTHS: &[&str] = &[
+    "januari",
+    "februari",
+    "maart",
+    "april",
+    "mei",
+    "juni",
+    "juli",
+    "augustus",
+    "september",
+    "oktober",
+    "november",
+    "december",
+];
+
+/// Information about a season page that is not stored in the database.
+#[derive(Debug, Deserialize)]
+pub struct PageMetadata {
+    pub cup: String,
+    pub season: i16,
+    pub title: Option<String>,
+    pub banner: Option<String>,
+    /// HTML shown below the ranking, e.g. the date of the prize ceremony.
+    pub notice: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub events: Vec<EventMetadata>,
+}
+
+/// Image and link of an event, matched to the database by date.
+/// Events that are not in the database yet are shown as planned events.
+#[derive(Debug, Deserialize)]
+pub struct EventMetadata {
+    pub date: NaiveDate,
+    pub label: Option<String>,
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub link: Option<String>,
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct EventCard {
+    date: String,
+    label: String,
+    title: String,
+    subtitle: String,
+    link: Option<String>,
+    image: String,
+}
+
+pub fn read_page_metadata(path: &Path) -> anyhow::Result<Vec<PageMetadata>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Render the page of a season.
+///
+/// The latest season of a cup is written to `<cup>.html`, older seasons to `<cup>-<season>.html`.
+/// When a new season starts, the existing `<cup>.html` is archived once the new page rendered.
+/// With `static_data`, the page reads the rankings `publish` wrote to that directory
+/// instead of calling the CGI program.
+/// Returns the path of the rendered page.
+pub fn render_season_page(
+    db: &dyn Database,
+    templates: &Path,
+    output: &Path,
+    metadata: &[PageMetadata],
+    cup: &str,
+    season: i16,
+    static_data: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    let page_metadata = metadata
+        .iter()
+        .find(|page| page.cup == cup && page.season == season);
+
+    let latest_season = list_seasons(db, cup)?
+        .into_iter()
+        .map(|info| info.season)
+        .chain(
+            metadata
+                .iter()
+                .filter(|page| page.cup == cup)
+                .map(|page| page.season),
+        )
+        .max()
+        .unwrap_or(season);
+    let (path, previous_page) = if season >= latest_season {
+        (
+            output.join(format!("{}.html", cup)),
+            previous_season_page(output, cup, season)?,
+        )
+    } else {
+        (output.join(format!("{}-{}.html", cup, season)), None)
+    };
+    let mut archive = archived_seasons(output, cup, season)?;
+    if let Some((previous_season, _)) = previous_page {
+        archive.push(previous_season);
+        archive.sort_unstable();
+    }
+
+    let events = event_cards(db, cup, season, page_metadata)?;
+    let categories = match page_metadata {
+        Some(page) if !page.categories.is_empty() => page.categories.clone(),
+        _ => list_categories(db, cup, season)?,
+    };
+    let title = match page_metadata.and_then(|page| page.title.clone()) {
+        Some(title) => title,
+        None => format!("{} {}", cup_title(cup), season),
+    };
+    let banner = match page_metadata.and_then(|page| page.banner.clone()) {
+        Some(banner) => Some(banner),
+        None => {
+            let banner = format!("images/{}-banner.jpg", cup);
+            output.join(&banner).exists().then_some(banner)
+        }
+    };
+
+    let mut env = Environment::new();
+    env.set_loader(path_loader(templates));
+    let template = env.get_template(&format!("{}.html", cup))?;
+    let page = template.render(context! {
+        cup,
+        season,
+        title,
+        banner,
+        events,
+        categories,
+        events_count => seasons::events_count(cup, season),
+        static_data,
+        notice => page_metadata.and_then(|page| page.notice.clone()),
+        archive,
+    })?;
+
+    // Only roll over to the new season once its page rendered
+    if let Some((previous_season, previous_page)) = previous_page {
+        let archive_path = output.join(format!("{}-{}.html", cup, previous_season));
+        eprintln!("Archiving {} to {}", path.display(), archive_path.display());
+        fs::write(archive_path, previous_page)?;
+    }
+    fs::write(&path, page)?;
+    Ok(path)
+}
+
+fn event_cards(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    page_metadata: Option<&PageMetadata>,
+) -> anyhow::Result<Vec<EventCard>> {
+    let event_metadata: &[EventMetadata] = page_metadata.map_or(&[], |page| &page.events);
+
+    let mut dates: Vec<NaiveDate> = vec![];
+    let mut cards: Vec<(NaiveDate, EventCard)> = vec![];
+    for event in list_events(db, cup, season)? {
+        let date = NaiveDate::parse_from_str(&event.date[..10.min(event.date.len())], "%Y-%m-%d")?;
+        let metadata = event_metadata.iter().find(|metadata| metadata.date == date);
+        dates.push(date);
+        cards.push((
+            date,
+            EventCard {
+                date: dutch_date(date),
+                label: String::new(),
+                title: metadata
+                    .and_then(|metadata| metadata.title.clone())
+                    .unwrap_or(event.location),
+                subtitle: metadata
+                    .and_then(|metadata| metadata.subtitle.clone())
+                    .unwrap_or(event.name),
+                link: metadata.and_then(|metadata| metadata.link.clone()),
+                image: image(metadata),
+            },
+        ));
+    }
+
+    // Planned events without results
+    for metadata in event_metadata {
+        if dates.contains(&metadata.date) {
+            continue;
+        }
+        cards.push((
+            metadata.date,
+            EventCard {
+                date: dutch_date(metadata.date),
+                label: String::new(),
+                title: metadata.title.clone().unwrap_or_default(),
+                subtitle: metadata.subtitle.clone().unwrap_or_default(),
+                link: metadata.link.clone(),
+                image: image(Some(metadata)),
+            },
+        ));
+    }
+
+    Ok(cards
+        .into_iter()
+        .sorted_by_key(|(date, _)| *date)
+        .enumerate()
+        .map(|(index, (date, card))| {
+            let label = event_metadata
+                .iter()
+                .find(|metadata| metadata.date == date)
+                .and_then(|metadata| metadata.label.clone())
+                .unwrap_or_else(|| format!("Etappe {}", index + 1));
+            EventCard { label, ..card }
+        })
+        .collect())
+}
+
+fn image(metadata: Option<&EventMetadata>) -> String {
+    metadata
+        .and_then(|metadata| metadata.image.clone())
+        .unwrap_or("images/question-mark.svg".to_owned())
+}
+
+// The page of the previous season and its season when a new season starts and it is not archived yet
+fn previous_season_page(
+    output: &Path,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<Option<(i16, String)>> {
+    let current_page = match fs::read_to_string(output.join(format!("{}.html", cup))) {
+        Ok(current_page) => current_page,
+        Err(_) => return Ok(None),
+    };
+
+    let season_re = Regex::new(r#"data-season="(\d+)""#).unwrap();
+    let current_season = season_re
+        .captures(&current_page)
+        .and_then(|captures| captures[1].parse::<i16>().ok());
+    Ok(current_season
+        .filter(|&current_season| {
+            current_season < season
+                && !output
+                    .join(format!("{}-{}.html", cup, current_season))
+                    .exists()
+        })
+        .map(|current_season| (current_season, current_page)))
+}
+
+fn archived_seasons(output: &Path, cup: &str, season: i16) -> anyhow::Result<Vec<i16>> {
+    let archive_re = Regex::new(&format!(r"^{}-(\d{{4}})\.html$", regex::escape(cup))).unwrap();
+    let mut seasons = vec![];
+    for entry in fs::read_dir(output)? {
+        let file_name = entry?.file_name();
+        if let Some(captures) = archive_re.captures(&file_name.to_string_lossy()) {
+            let archived_season: i16 = captures[1].parse()?;
+            if archived_season < season {
+                seasons.push(archived_season);
+            }
+        }
+    }
+    seasons.sort_unstable();
+    Ok(seasons)
+}
+
+pub(crate) fn cup_title(cup: &str) -> &str {
+    match cup {
+        "forest-cup" => "Forest Cup",
+        "city-cup" => "City Cup",
+        "kampioen" => "Vlaams kampioen",
+        "vlaamse-beker" => "Vlaamse beker",
+        _ => cup,
+    }
+}
+
+pub(crate) fn dutch_date(date: NaiveDate) -> String {
+    format!(
+        "{} {} {}",
+        date.day(),
+        MONTHS[date.month0() as usize],
+        date.year()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use chrono::NaiveDate;
+
+    use crate::testing::{add_event, add_result, database};
+
+    use super::{dutch_date, render_season_page, PageMetadata};
+
+    #[test]
+    fn season_rollover() {
+        let db = database();
+        let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+        let output = std::env::temp_dir().join(format!("ov-cup-pages-{}", std::process::id()));
+        fs::create_dir_all(&output).unwrap();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            event_id,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+
+        let path =
+            render_season_page(&db, &templates, &output, &[], "forest-cup", 2026, None).unwrap();
+        assert_eq!(path, output.join("forest-cup.html"));
+        let page = fs::read_to_string(&path).unwrap();
+        assert!(page.contains(r#"data-season="2026""#));
+        assert!(page.contains("<span>H21</span>"));
+        assert!(page.contains("17 januari 2026"));
+        assert!(!page.contains("data-static"));
+
+        // A new season archives the page of the previous one
+        let event_id = add_event(&db, "forest-cup", 2027, "2027-01-16");
+        add_result(
+            &db,
+            event_id,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        render_season_page(
+            &db,
+            &templates,
+            &output,
+            &[],
+            "forest-cup",
+            2027,
+            Some("data"),
+        )
+        .unwrap();
+        let archived = fs::read_to_string(output.join("forest-cup-2026.html")).unwrap();
+        assert_eq!(archived, page);
+        let page = fs::read_to_string(&path).unwrap();
+        assert!(page.contains(r#"data-season="2027" data-events="4" data-static="data""#));
+        assert!(page.contains(r#"<a href="forest-cup-2026.html">2026</a>"#));
+
+        // A page that fails to render doesn't roll the site over
+        let metadata = vec![PageMetadata {
+            cup: "forest-cup".to_owned(),
+            season: 2028,
+            title: None,
+            banner: None,
+            notice: None,
+            categories: vec![],
+            events: vec![],
+        }];
+        assert!(render_season_page(
+            &db,
+            &output.join("missing"),
+            &output,
+            &metadata,
+            "forest-cup",
+            2028,
+            None
+        )
+        .is_err());
+        assert!(!output.join("forest-cup-2027.html").exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), page);
+
+        fs::remove_dir_all(&output).unwrap();
+    }
+
+    #[test]
+    fn date() {
+        assert_eq!(
+            dutch_date(NaiveDate::from_ymd_opt(2025, 11, 23).unwrap()),
+            "23 november 2025"
+        );
+    }
+}
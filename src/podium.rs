@@ -0,0 +1,183 @@
+// Top runners of every category for the prize ceremony
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    db::Database, discovery::list_categories, ranking_places, seasons, snapshots::official_ranking,
+    RankingEntry,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PodiumFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid podium format, valid formats are: csv, markdown, json")]
+pub struct UnknownPodiumFormat;
+
+impl FromStr for PodiumFormat {
+    type Err = UnknownPodiumFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(PodiumFormat::Csv),
+            "markdown" => Ok(PodiumFormat::Markdown),
+            "json" => Ok(PodiumFormat::Json),
+            _ => Err(UnknownPodiumFormat),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PodiumPlace {
+    pub place: u32,
+    pub name: String,
+    pub club: String,
+    #[serde(rename = "totalScore")]
+    pub total_score: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryPodium {
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub places: Vec<PodiumPlace>,
+}
+
+/// The runners on the first `places` places of every category.
+///
+/// Runners with the same total share a place, so a category can have more than `places` runners.
+/// Without `age_classes` all categories of the season are listed.
+pub fn podium(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_classes: &[String],
+    places: u32,
+) -> anyhow::Result<Vec<CategoryPodium>> {
+    let age_classes = if age_classes.is_empty() {
+        list_categories(db, cup, season)?
+    } else {
+        age_classes.to_vec()
+    };
+
+    let mut podiums = vec![];
+    for age_class in age_classes {
+        let ranking = official_ranking(
+            db,
+            cup.to_owned(),
+            season,
+            age_class.clone(),
+            seasons::events_count(cup, season),
+        )?;
+        podiums.push(CategoryPodium {
+            age_class,
+            places: podium_places(&ranking, places),
+        });
+    }
+    Ok(podiums)
+}
+
+fn podium_places(ranking: &[RankingEntry], places: u32) -> Vec<PodiumPlace> {
+    ranking
+        .iter()
+        .zip(ranking_places(ranking))
+        .take_while(|(_, place)| *place <= places)
+        .map(|(entry, place)| PodiumPlace {
+            place,
+            name: entry.name.clone(),
+            club: entry.club.clone(),
+            total_score: entry.total_score,
+        })
+        .collect()
+}
+
+pub fn format_podium(podiums: &[CategoryPodium], format: PodiumFormat) -> anyhow::Result<String> {
+    match format {
+        PodiumFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            writer.write_record(["Categorie", "Plaats", "Naam", "Club", "Totaal"])?;
+            for podium in podiums {
+                for place in &podium.places {
+                    writer.write_record([
+                        podium.age_class.clone(),
+                        place.place.to_string(),
+                        place.name.clone(),
+                        place.club.clone(),
+                        place.total_score.to_string(),
+                    ])?;
+                }
+            }
+            Ok(String::from_utf8(writer.into_inner()?)?)
+        }
+        PodiumFormat::Markdown => {
+            let mut output = String::new();
+            for podium in podiums {
+                output.push_str(&format!("## {}\n\n", podium.age_class));
+                output.push_str("| Plaats | Naam | Club | Totaal |\n");
+                output.push_str("| -----: | ---- | ---- | -----: |\n");
+                for place in &podium.places {
+                    output.push_str(&format!(
+                        "| {}. | {} | {} | {} |\n",
+                        place.place,
+                        markdown_escape(&place.name),
+                        markdown_escape(&place.club),
+                        place.total_score
+                    ));
+                }
+                output.push('\n');
+            }
+            Ok(output)
+        }
+        PodiumFormat::Json => Ok(serde_json::to_string_pretty(podiums)?),
+    }
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::entry;
+
+    use super::podium_places;
+
+    #[test]
+    fn shared_third_place() {
+        let ranking = vec![
+            entry("A", 3000),
+            entry("B", 2900),
+            entry("C", 2800),
+            entry("D", 2800),
+            entry("E", 2700),
+        ];
+        let places: Vec<(u32, String)> = podium_places(&ranking, 3)
+            .into_iter()
+            .map(|place| (place.place, place.name))
+            .collect();
+        assert_eq!(
+            places,
+            vec![
+                (1, "A".to_owned()),
+                (2, "B".to_owned()),
+                (3, "C".to_owned()),
+                (3, "D".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_first_place() {
+        let ranking = vec![entry("A", 3000), entry("B", 3000), entry("C", 2900)];
+        assert_eq!(podium_places(&ranking, 2).len(), 2);
+    }
+}
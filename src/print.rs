@@ -0,0 +1,481 @@
+// Printable ranking sheets
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+use minijinja::{context, path_loader, Environment};
+use printpdf::{
+    lopdf::Document, BuiltinFont, Color, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point, Rgb,
+};
+use serde::Serialize;
+
+use crate::{
+    db::Database,
+    discovery::{list_categories, list_events},
+    pages::{cup_title, dutch_date},
+    ranking_places, seasons,
+    snapshots::official_ranking,
+};
+
+const PAGE_WIDTH: f32 = 297.0;
+const PAGE_HEIGHT: f32 = 210.0;
+const MARGIN: f32 = 15.0;
+const ROW_HEIGHT: f32 = 6.0;
+const FONT_SIZE: f32 = 9.0;
+
+#[derive(Debug, Serialize)]
+pub struct SheetEvent {
+    pub name: String,
+    pub date: String,
+    pub location: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SheetScore {
+    pub score: Option<u32>,
+    pub place: Option<u32>,
+    pub compensation: bool,
+    pub counted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SheetRow {
+    pub place: u32,
+    pub name: String,
+    pub club: String,
+    pub scores: Vec<SheetScore>,
+    pub total: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SheetCategory {
+    pub age_class: String,
+    pub rows: Vec<SheetRow>,
+}
+
+/// Standings of every category of a season, ready to be printed.
+#[derive(Debug, Serialize)]
+pub struct RankingSheets {
+    pub title: String,
+    pub events_count: usize,
+    pub events: Vec<SheetEvent>,
+    pub categories: Vec<SheetCategory>,
+}
+
+pub fn ranking_sheets(db: &dyn Database, cup: &str, season: i16) -> anyhow::Result<RankingSheets> {
+    let events = list_events(db, cup, season)?;
+    let events_count = seasons::events_count(cup, season);
+
+    let mut categories = vec![];
+    for age_class in list_categories(db, cup, season)? {
+        let ranking =
+            official_ranking(db, cup.to_owned(), season, age_class.clone(), events_count)?;
+        let rows = ranking
+            .iter()
+            .zip(ranking_places(&ranking))
+            .map(|(entry, place)| SheetRow {
+                place,
+                name: entry.name.clone(),
+                club: entry.club.clone(),
+                scores: events
+                    .iter()
+                    .map(|event| {
+                        let score = entry.scores.iter().find(|score| score.event_id == event.id);
+                        SheetScore {
+                            score: score.and_then(|score| score.score),
+                            place: score.and_then(|score| score.place),
+                            compensation: score.is_some_and(|score| score.compensation),
+                            counted: score.is_some_and(|score| score.counted),
+                        }
+                    })
+                    .collect(),
+                total: entry.total_score,
+            })
+            .collect();
+        categories.push(SheetCategory { age_class, rows });
+    }
+
+    Ok(RankingSheets {
+        title: format!("{} {}", cup_title(cup), season),
+        events_count,
+        events: events
+            .into_iter()
+            .map(|event| SheetEvent {
+                date: NaiveDate::parse_from_str(
+                    &event.date[..10.min(event.date.len())],
+                    "%Y-%m-%d",
+                )
+                .map_or(event.date.clone(), dutch_date),
+                name: event.name,
+                location: event.location,
+            })
+            .collect(),
+        categories,
+    })
+}
+
+/// Render the sheets with the `print.html` template.
+pub fn render_html(templates: &Path, sheets: &RankingSheets) -> anyhow::Result<String> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(templates));
+    let template = env.get_template("print.html")?;
+    Ok(template.render(context! { sheets })?)
+}
+
+/// Render the sheets as an A4 landscape PDF.
+///
+/// Categories start on a new page unless their header and first rows fit on the current one.
+pub fn render_pdf(sheets: &RankingSheets) -> anyhow::Result<Vec<u8>> {
+    let (doc, page, layer) =
+        PdfDocument::new(&sheets.title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Ranking");
+    let fonts = Fonts {
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+    };
+    let mut sheet = Sheet {
+        layer: doc.get_page(page).get_layer(layer),
+        y: PAGE_HEIGHT - MARGIN,
+        page: 1,
+    };
+    sheet.title(sheets, &fonts);
+
+    let columns = Columns::new(sheets.events.len());
+    for category in &sheets.categories {
+        let rows_needed = category.rows.len().min(3) as f32 + 2.0;
+        if sheet.y - rows_needed * ROW_HEIGHT < MARGIN {
+            sheet.new_page(&doc, sheets, &fonts);
+        }
+        sheet.y -= ROW_HEIGHT;
+        sheet.layer.use_text(
+            &category.age_class,
+            12.0,
+            Mm(MARGIN),
+            Mm(sheet.y),
+            &fonts.bold,
+        );
+        sheet.y -= ROW_HEIGHT;
+        sheet.header(&columns, sheets, &fonts);
+
+        for row in &category.rows {
+            if sheet.y - ROW_HEIGHT < MARGIN {
+                sheet.new_page(&doc, sheets, &fonts);
+                sheet.header(&columns, sheets, &fonts);
+            }
+            sheet.row(&columns, row, &fonts);
+        }
+    }
+
+    Ok(doc.save_to_bytes()?)
+}
+
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+}
+
+struct Columns {
+    place: f32,
+    name: f32,
+    club: f32,
+    events: f32,
+    event_width: f32,
+    total: f32,
+}
+
+impl Columns {
+    fn new(events: usize) -> Self {
+        let place = MARGIN;
+        let name = place + 12.0;
+        let club = name + 60.0;
+        let events_start = club + 50.0;
+        let total = PAGE_WIDTH - MARGIN - 15.0;
+        let event_width = if events == 0 {
+            0.0
+        } else {
+            ((total - events_start) / events as f32).min(22.0)
+        };
+        Columns {
+            place,
+            name,
+            club,
+            events: events_start,
+            event_width,
+            total,
+        }
+    }
+
+    fn event(&self, index: usize) -> f32 {
+        self.events + index as f32 * self.event_width
+    }
+}
+
+struct Sheet {
+    layer: PdfLayerReference,
+    y: f32,
+    page: usize,
+}
+
+impl Sheet {
+    fn title(&mut self, sheets: &RankingSheets, fonts: &Fonts) {
+        self.y -= 2.0;
+        self.layer
+            .use_text(&sheets.title, 16.0, Mm(MARGIN), Mm(self.y), &fonts.bold);
+        self.y -= ROW_HEIGHT;
+        for (index, event) in sheets.events.iter().enumerate() {
+            self.y -= 4.5;
+            self.layer.use_text(
+                win_ansi(&format!(
+                    "{}. {} - {} - {}",
+                    index + 1,
+                    event.date,
+                    event.location,
+                    event.name
+                )),
+                FONT_SIZE,
+                Mm(MARGIN),
+                Mm(self.y),
+                &fonts.regular,
+            );
+        }
+        self.y -= 4.5;
+        self.layer.use_text(
+            format!(
+                "De beste {} resultaten tellen, doorstreepte resultaten tellen niet mee, * compensatiepunten organisatie.",
+                sheets.events_count
+            ),
+            FONT_SIZE,
+            Mm(MARGIN),
+            Mm(self.y),
+            &fonts.regular,
+        );
+        self.footer(fonts);
+    }
+
+    fn footer(&self, fonts: &Fonts) {
+        self.layer.use_text(
+            format!("Pagina {}", self.page),
+            FONT_SIZE,
+            Mm(PAGE_WIDTH - MARGIN - 15.0),
+            Mm(MARGIN / 2.0),
+            &fonts.regular,
+        );
+    }
+
+    fn new_page(&mut self, doc: &PdfDocumentReference, sheets: &RankingSheets, fonts: &Fonts) {
+        let (page, layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Ranking");
+        self.layer = doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.page += 1;
+        self.layer.use_text(
+            &sheets.title,
+            FONT_SIZE,
+            Mm(MARGIN),
+            Mm(self.y),
+            &fonts.regular,
+        );
+        self.y -= ROW_HEIGHT;
+        self.footer(fonts);
+    }
+
+    fn header(&mut self, columns: &Columns, sheets: &RankingSheets, fonts: &Fonts) {
+        let bold = &fonts.bold;
+        self.layer
+            .use_text("Pl.", FONT_SIZE, Mm(columns.place), Mm(self.y), bold);
+        self.layer
+            .use_text("Naam", FONT_SIZE, Mm(columns.name), Mm(self.y), bold);
+        self.layer
+            .use_text("Club", FONT_SIZE, Mm(columns.club), Mm(self.y), bold);
+        for index in 0..sheets.events.len() {
+            self.layer.use_text(
+                format!("{}.", index + 1),
+                FONT_SIZE,
+                Mm(columns.event(index)),
+                Mm(self.y),
+                bold,
+            );
+        }
+        self.layer
+            .use_text("Totaal", FONT_SIZE, Mm(columns.total), Mm(self.y), bold);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y - 1.5)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y - 1.5)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= ROW_HEIGHT;
+    }
+
+    fn row(&mut self, columns: &Columns, row: &SheetRow, fonts: &Fonts) {
+        let font = &fonts.regular;
+        self.layer.use_text(
+            format!("{}.", row.place),
+            FONT_SIZE,
+            Mm(columns.place),
+            Mm(self.y),
+            font,
+        );
+        self.layer.use_text(
+            truncate(&win_ansi(&row.name), 34),
+            FONT_SIZE,
+            Mm(columns.name),
+            Mm(self.y),
+            font,
+        );
+        self.layer.use_text(
+            truncate(&win_ansi(&row.club), 28),
+            FONT_SIZE,
+            Mm(columns.club),
+            Mm(self.y),
+            font,
+        );
+        for (index, score) in row.scores.iter().enumerate() {
+            let Some(points) = score.score else {
+                continue;
+            };
+            let text = if score.compensation {
+                format!("{}*", points)
+            } else {
+                points.to_string()
+            };
+            let x = columns.event(index);
+            if score.counted {
+                self.layer
+                    .use_text(&text, FONT_SIZE, Mm(x), Mm(self.y), font);
+            } else {
+                self.dropped(&text, x, font);
+            }
+        }
+        self.layer.use_text(
+            row.total.to_string(),
+            FONT_SIZE,
+            Mm(columns.total),
+            Mm(self.y),
+            &fonts.bold,
+        );
+        self.y -= ROW_HEIGHT;
+    }
+
+    // Grey and struck through
+    fn dropped(&self, text: &str, x: f32, font: &IndirectFontRef) {
+        let grey = Color::Rgb(Rgb::new(0.5, 0.5, 0.5, None));
+        self.layer.set_fill_color(grey.clone());
+        self.layer.set_outline_color(grey);
+        self.layer
+            .use_text(text, FONT_SIZE, Mm(x), Mm(self.y), font);
+        // Helvetica digits are 0.556 em wide
+        let width = text.len() as f32 * 0.556 * FONT_SIZE * 25.4 / 72.0;
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(x), Mm(self.y + 1.1)), false),
+                (Point::new(Mm(x + width), Mm(self.y + 1.1)), false),
+            ],
+            is_closed: false,
+        });
+        let black = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+        self.layer.set_fill_color(black.clone());
+        self.layer.set_outline_color(black);
+    }
+}
+
+// The builtin fonts only have WinAnsi glyphs, other characters would silently disappear
+fn win_ansi(text: &str) -> String {
+    text.chars()
+        .map(|character| {
+            let mut buffer = [0; 4];
+            let encoded =
+                Document::encode_text(Some("WinAnsiEncoding"), character.encode_utf8(&mut buffer));
+            if encoded.is_empty() {
+                '?'
+            } else {
+                character
+            }
+        })
+        .collect()
+}
+
+fn truncate(text: &str, length: usize) -> String {
+    if text.chars().count() > length {
+        let mut truncated: String = text.chars().take(length - 1).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        text.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::testing::{add_event, add_result, database};
+
+    use super::{ranking_sheets, render_html, render_pdf, truncate, win_ansi};
+
+    #[test]
+    fn sheets_with_names_outside_win_ansi() {
+        let db = database();
+        let first = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        let second = add_event(&db, "forest-cup", 2026, "2026-02-14");
+        for (event_id, name, time) in [
+            (first, "Zoë Ćosić", "01:00:00"),
+            (first, "Łukasz Żółw", "01:20:00"),
+            (second, "Łukasz Żółw", "01:00:00"),
+            (second, "王伟", "01:10:00"),
+        ] {
+            add_result(
+                &db,
+                event_id,
+                name,
+                "Omega",
+                "H:Zwart Extra Lang",
+                "H21",
+                time,
+            );
+        }
+
+        let sheets = ranking_sheets(&db, "forest-cup", 2026).unwrap();
+        assert_eq!(sheets.title, "Forest Cup 2026");
+        assert_eq!(sheets.events.len(), 2);
+        assert_eq!(sheets.events[1].date, "14 februari 2026");
+        assert_eq!(sheets.categories.len(), 1);
+        let rows: Vec<(u32, &str, Vec<Option<u32>>, u32)> = sheets.categories[0]
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.place,
+                    row.name.as_str(),
+                    row.scores.iter().map(|score| score.score).collect(),
+                    row.total,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1, "Łukasz Żółw", vec![Some(750), Some(1000)], 1750),
+                (2, "Zoë Ćosić", vec![Some(1000), None], 1000),
+                (3, "王伟", vec![None, Some(857)], 857),
+            ]
+        );
+
+        let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+        let html = render_html(&templates, &sheets).unwrap();
+        assert!(html.contains("Zoë Ćosić"));
+        assert!(html.contains("王伟"));
+        let pdf = render_pdf(&sheets).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+        assert_eq!(win_ansi("Zoë Ćosić"), "Zoë ?osi?");
+        assert_eq!(win_ansi("王伟 – Œ…"), "?? – Œ…");
+    }
+
+    #[test]
+    fn truncate_long_names() {
+        assert_eq!(truncate("Orienteering Vlaanderen", 12), "Orienteerin…");
+        assert_eq!(truncate("Omega", 12), "Omega");
+    }
+}
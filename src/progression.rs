@@ -0,0 +1,141 @@
+// Standings after every event of a season
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    calculate_ranking_with, db::Database, discovery::list_events, ranking_places, AsOf,
+    RankingEntry, RankingOptions,
+};
+
+#[derive(Debug, Serialize)]
+pub struct ProgressionEvent {
+    pub id: i64,
+    pub name: String,
+    pub date: String,
+}
+
+/// Place and total score of a runner after each event, `None` before their first result.
+#[derive(Debug, Serialize)]
+pub struct RunnerProgression {
+    pub name: String,
+    pub club: String,
+    pub places: Vec<Option<u32>>,
+    #[serde(rename = "totalScores")]
+    pub total_scores: Vec<Option<u32>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankingProgression {
+    pub events: Vec<ProgressionEvent>,
+    /// In the order of the current ranking.
+    pub runners: Vec<RunnerProgression>,
+}
+
+/// The ranking of a category after each event of the season.
+pub fn ranking_progression(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+) -> anyhow::Result<RankingProgression> {
+    let mut events = list_events(db, cup, season)?;
+    // Events of a combined cup are listed per contributing cup
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut rankings = vec![];
+    for event in &events {
+        let options = RankingOptions {
+            as_of: Some(AsOf::Event(event.id)),
+            ..Default::default()
+        };
+        rankings.push(calculate_ranking_with(
+            db,
+            cup.to_owned(),
+            season,
+            age_class.to_owned(),
+            events_count,
+            &options,
+        )?);
+    }
+
+    Ok(RankingProgression {
+        events: events
+            .into_iter()
+            .map(|event| ProgressionEvent {
+                id: event.id,
+                name: event.name,
+                date: event.date,
+            })
+            .collect(),
+        runners: progression(&rankings),
+    })
+}
+
+fn progression(rankings: &[Vec<RankingEntry>]) -> Vec<RunnerProgression> {
+    let Some(current) = rankings.last() else {
+        return vec![];
+    };
+    let standings: Vec<HashMap<&str, (u32, u32)>> = rankings
+        .iter()
+        .map(|ranking| {
+            ranking
+                .iter()
+                .zip(ranking_places(ranking))
+                .map(|(entry, place)| (entry.name.as_str(), (place, entry.total_score)))
+                .collect()
+        })
+        .collect();
+
+    current
+        .iter()
+        .map(|entry| {
+            let (places, total_scores) = standings
+                .iter()
+                .map(|standing| match standing.get(entry.name.as_str()) {
+                    Some(&(place, total_score)) => (Some(place), Some(total_score)),
+                    None => (None, None),
+                })
+                .unzip();
+            RunnerProgression {
+                name: entry.name.clone(),
+                club: entry.club.clone(),
+                places,
+                total_scores,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::entry;
+
+    use super::progression;
+
+    #[test]
+    fn places_after_each_event() {
+        let rankings = vec![
+            vec![entry("A", 1000), entry("B", 900)],
+            vec![entry("B", 1900), entry("A", 1000), entry("C", 1000)],
+        ];
+        let runners = progression(&rankings);
+        let places: Vec<(&str, Vec<Option<u32>>)> = runners
+            .iter()
+            .map(|runner| (runner.name.as_str(), runner.places.clone()))
+            .collect();
+        assert_eq!(
+            places,
+            vec![
+                ("B", vec![Some(2), Some(1)]),
+                ("A", vec![Some(1), Some(2)]),
+                ("C", vec![None, Some(2)]),
+            ]
+        );
+        assert_eq!(runners[2].total_scores, vec![None, Some(1000)]);
+    }
+}
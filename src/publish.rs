@@ -0,0 +1,133 @@
+// Precomputed rankings for a static site
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fs, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    combined::COMBINED_CUPS,
+    db::Database,
+    discovery::{list_categories, list_cups, list_events, list_seasons, EventInfo},
+    seasons,
+    snapshots::official_ranking,
+};
+
+#[derive(Debug, Serialize)]
+struct SeasonIndex {
+    cup: String,
+    season: i16,
+    #[serde(rename = "eventsCount")]
+    events_count: usize,
+    categories: Vec<String>,
+    events: Vec<EventInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct CupIndex {
+    cup: String,
+    seasons: Vec<i16>,
+}
+
+/// Write the ranking of every cup, season and category as JSON files below `directory`.
+///
+/// Rankings end up in `<cup>/<season>/<category>.json`, next to an `index.json`
+/// with the categories and events of the season. Frozen rankings are published as they were frozen.
+/// Rankings of categories that are no longer in a season are removed.
+/// Returns the number of rankings that were written.
+pub fn publish(db: &dyn Database, directory: &Path) -> anyhow::Result<usize> {
+    let mut published = 0;
+    let mut cups = vec![];
+
+    for cup in list_cups(db)? {
+        let seasons: Vec<i16> = list_seasons(db, &cup)?
+            .into_iter()
+            .map(|season| season.season)
+            .collect();
+        for &season in &seasons {
+            let index = SeasonIndex {
+                cup: cup.clone(),
+                season,
+                events_count: seasons::events_count(&cup, season),
+                categories: list_categories(db, &cup, season)?,
+                events: list_events(db, &cup, season)?,
+            };
+            published += publish_season(db, directory, index)?;
+        }
+        cups.push(CupIndex { cup, seasons });
+    }
+
+    for combined_cup in COMBINED_CUPS {
+        let events = list_events(db, combined_cup.name, combined_cup.season)?;
+        if events.is_empty() {
+            continue;
+        }
+        let index = SeasonIndex {
+            cup: combined_cup.name.to_owned(),
+            season: combined_cup.season,
+            events_count: seasons::events_count(combined_cup.name, combined_cup.season),
+            categories: list_categories(db, combined_cup.name, combined_cup.season)?,
+            events,
+        };
+        published += publish_season(db, directory, index)?;
+        match cups.iter_mut().find(|index| index.cup == combined_cup.name) {
+            Some(index) => index.seasons.push(combined_cup.season),
+            None => cups.push(CupIndex {
+                cup: combined_cup.name.to_owned(),
+                seasons: vec![combined_cup.season],
+            }),
+        }
+    }
+
+    write_json(&directory.join("index.json"), &cups)?;
+    Ok(published)
+}
+
+fn publish_season(
+    db: &dyn Database,
+    directory: &Path,
+    index: SeasonIndex,
+) -> anyhow::Result<usize> {
+    let season_directory = directory.join(&index.cup).join(index.season.to_string());
+    fs::create_dir_all(&season_directory)?;
+
+    for age_class in &index.categories {
+        let ranking = official_ranking(
+            db,
+            index.cup.clone(),
+            index.season,
+            age_class.to_owned(),
+            index.events_count,
+        )?;
+        write_json(
+            &season_directory.join(format!("{}.json", age_class)),
+            &ranking,
+        )?;
+    }
+
+    write_json(&season_directory.join("index.json"), &index)?;
+
+    // Remove the rankings of categories that no longer exist
+    for entry in fs::read_dir(&season_directory)? {
+        let path = entry?.path();
+        let stale = path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+            && path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| {
+                    stem != "index" && !index.categories.iter().any(|category| category == stem)
+                });
+        if stale {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(index.categories.len())
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> anyhow::Result<()> {
+    fs::write(path, serde_json::to_vec(value)?)?;
+    Ok(())
+}
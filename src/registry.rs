@@ -0,0 +1,192 @@
+// Registry of runners that decides who is ranked
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+
+use crate::{db::Database, is_ov_club, seasons::Eligibility};
+
+/// What the federation knows about a runner.
+#[derive(Debug, Default, Deserialize)]
+pub struct Registration {
+    pub name: String,
+    pub federation: Option<String>,
+    pub nationality: Option<String>,
+    pub licence: Option<String>,
+}
+
+/// Store the registry, replacing the stored registry.
+pub fn store_registry(db: &dyn Database, registrations: &[Registration]) -> anyhow::Result<()> {
+    let conn = db.open()?;
+    conn.execute("delete from Registration", [])?;
+    for registration in registrations {
+        conn.execute(
+            "
+            insert into Runner (name, club) values (?, '')
+            on conflict (name) do nothing
+        ",
+            params![registration.name],
+        )?;
+        conn.execute(
+            "
+            insert into Registration (runner_id, federation, nationality, licence)
+            select id, ?, ?, ? from Runner where name = ?
+        ",
+            params![
+                registration.federation,
+                registration.nationality,
+                registration.licence,
+                registration.name
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Runners with results in a season who don't meet its eligibility.
+///
+/// The club is the club of the runner's last result in the season, so later seasons for
+/// another club don't change who was ranked.
+pub(crate) fn find_guests(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+    eligibility: &Eligibility,
+) -> anyhow::Result<HashSet<String>> {
+    if *eligibility == Eligibility::OPEN {
+        return Ok(HashSet::new());
+    }
+    let mut stmt = conn.prepare(
+        "
+        select
+            Runner.name,
+            Result.club,
+            Registration.federation,
+            Registration.nationality,
+            Registration.licence
+        from Result join Runner on Result.runner_id = Runner.id
+                    join Event on Result.event_id = Event.id
+                    left join Registration on Registration.runner_id = Runner.id
+        where Event.cup = ? and Event.season = ?
+        order by Event.date asc, Result.id asc
+    ",
+    )?;
+    let mut runners: HashMap<String, (String, Registration)> = HashMap::new();
+    for row in stmt.query_map(params![cup, season], |row| {
+        Ok((
+            row.get::<_, String>(1)?,
+            Registration {
+                name: row.get(0)?,
+                federation: row.get(2)?,
+                nationality: row.get(3)?,
+                licence: row.get(4)?,
+            },
+        ))
+    })? {
+        let (club, registration) = row?;
+        runners.insert(registration.name.clone(), (club, registration));
+    }
+    Ok(runners
+        .into_values()
+        .filter(|(club, registration)| !is_eligible(eligibility, club, registration))
+        .map(|(_, registration)| registration.name)
+        .collect())
+}
+
+fn is_eligible(eligibility: &Eligibility, club: &str, registration: &Registration) -> bool {
+    (!eligibility.ov_club || is_ov_club(club))
+        && eligibility
+            .federation
+            .is_none_or(|federation| registration.federation.as_deref() == Some(federation))
+        && eligibility
+            .nationality
+            .is_none_or(|nationality| registration.nationality.as_deref() == Some(nationality))
+        && (!eligibility.licence || registration.licence.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calculate_ranking,
+        seasons::Eligibility,
+        testing::{add_event, add_result, database},
+    };
+
+    use super::{is_eligible, Registration};
+
+    #[test]
+    fn eligibility() {
+        let registration = Registration {
+            name: "Jan Janssens".to_owned(),
+            federation: Some("OV".to_owned()),
+            nationality: Some("BEL".to_owned()),
+            licence: None,
+        };
+        assert!(is_eligible(&Eligibility::OPEN, "Foreign OK", &registration));
+
+        let ov_club = Eligibility {
+            ov_club: true,
+            ..Eligibility::OPEN
+        };
+        assert!(is_eligible(&ov_club, "Omega", &registration));
+        assert!(!is_eligible(&ov_club, "Foreign OK", &registration));
+
+        let belgian = Eligibility {
+            nationality: Some("BEL"),
+            federation: Some("OV"),
+            ..Eligibility::OPEN
+        };
+        assert!(is_eligible(&belgian, "Foreign OK", &registration));
+        assert!(!is_eligible(&belgian, "Omega", &Registration::default()));
+
+        let licence = Eligibility {
+            licence: true,
+            ..Eligibility::OPEN
+        };
+        assert!(!is_eligible(&licence, "Omega", &registration));
+    }
+
+    #[test]
+    fn guests_of_the_championship() {
+        let db = database();
+        let event = add_event(&db, "kampioen", 2023, "2023-05-06");
+        add_result(&db, event, "A", "Omega", "Lang", "H21", "01:00:00");
+        add_result(&db, event, "B", "Trol", "Lang", "H21", "00:50:00");
+        add_result(&db, event, "G", "Foreign OK", "Lang", "H21", "00:40:00");
+        add_result(&db, event, "X", "Foreign OK", "Lang", "H35", "00:30:00");
+
+        // Guests are not ranked and don't set the reference time
+        let ranking =
+            calculate_ranking(&db, "kampioen".to_owned(), 2023, "H21".to_owned(), 2).unwrap();
+        let totals: Vec<(&str, u32)> = ranking
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.total_score))
+            .collect();
+        assert_eq!(totals, vec![("B", 1000), ("A", 833)]);
+    }
+
+    #[test]
+    fn club_of_the_season() {
+        let db = database();
+        let event = add_event(&db, "kampioen", 2023, "2023-05-06");
+        add_result(&db, event, "A", "Omega", "Lang", "H21", "01:00:00");
+        add_result(&db, event, "B", "Trol", "Lang", "H21", "00:50:00");
+        // A moves abroad, the latest load changes the club of the runner
+        let event = add_event(&db, "kampioen", 2024, "2024-05-04");
+        add_result(&db, event, "A", "Foreign OK", "Lang", "H21", "01:00:00");
+        add_result(&db, event, "B", "Trol", "Lang", "H21", "00:50:00");
+
+        let ranked = |season| -> Vec<String> {
+            calculate_ranking(&db, "kampioen".to_owned(), season, "H21".to_owned(), 2)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect()
+        };
+        assert_eq!(ranked(2023), vec!["B", "A"]);
+        assert_eq!(ranked(2024), vec!["B"]);
+    }
+}
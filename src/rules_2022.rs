@@ -1,13 +1,13 @@
 // SPDX-FileCopyrightText: 2023 Jeroen Hoekx
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::collections::HashMap;
-
-use chrono::NaiveTime;
 use itertools::Itertools;
 use rusqlite::params;
 
-use crate::{db::Database, total_seconds, Performance, RankingEntry, RankingScore};
+use crate::{
+    class_changes::ClassChanges, db::Database, reference_times, total_seconds,
+    wrong_course_performances, Performance, RankingContext, RankingEntry, RankingScore,
+};
 
 pub(crate) fn calculate_ranking(
     db: &dyn Database,
@@ -15,14 +15,21 @@ pub(crate) fn calculate_ranking(
     season: i16,
     age_class: String,
     events_count: usize,
+    context: &mut RankingContext,
 ) -> Result<Vec<RankingEntry>, anyhow::Error> {
     let conn = db.open()?;
 
     // Find all events
-    let mut stmt =
-        conn.prepare("select id from Event where cup = ? and season = ? order by date asc")?;
+    let mut stmt = conn.prepare(
+        "
+        select id from Event
+        where cup = ? and season = ?
+          and julianday(date) <= julianday(coalesce(?, date))
+        order by date asc
+    ",
+    )?;
     let events: Vec<i64> = stmt
-        .query_map(params![cup, season], |row| {
+        .query_map(params![cup, season, context.until], |row| {
             let event_id = row.get(0)?;
             Ok(event_id)
         })?
@@ -34,7 +41,7 @@ pub(crate) fn calculate_ranking(
         "
         select
             Runner.name,
-            Runner.club,
+            Result.club,
             Event.id,
             Result.age_class,
             Result.category_name,
@@ -43,6 +50,7 @@ pub(crate) fn calculate_ranking(
         from Result join Runner on Result.runner_id = Runner.id
                     join Event on Result.event_id = Event.id
         where Event.cup = ? and Event.season = ?
+          and not Result.wrong_course
           and Runner.id in (
               select Runner.id
               from Runner join Result on Runner.id = Result.Runner_id
@@ -65,45 +73,38 @@ pub(crate) fn calculate_ranking(
                 score: 0,
             })
         })?
-        .filter_map(|r| r.ok());
+        .filter_map(|r| r.ok())
+        // Only events up to the requested moment count
+        .filter(|result| events.contains(&result.event_id));
 
     // TODO: Keep only courses that are valid for the age class of the result
 
     // Keep only results of runners where the last age class equals the given age class
     // This filters out runners who moved to a different category,
     // while keeping the runners that moved into this category.
+    // A class history replaces the last age class.
+    let class_changes = ClassChanges::find(&conn, &cup, season, &age_class, context.until)?;
     let mut results = Vec::new();
-    for (_, runner_results) in &all_results
+    for (name, runner_results) in &all_results
         .into_iter()
         .chunk_by(|result| result.name.to_owned())
     {
         let mut runner_results: Vec<Performance> = runner_results.collect();
-        if runner_results.last().unwrap().age_class == age_class {
+        if class_changes.has_runner(&name) {
+            if class_changes.changed_to(&name) {
+                runner_results.retain(|result| {
+                    result.age_class == age_class || class_changes.carries_over(result)
+                });
+                results.append(&mut runner_results);
+            }
+        } else if runner_results.last().unwrap().age_class == age_class {
             results.append(&mut runner_results);
         }
     }
 
-    // Find the best results in all courses that someone of the given age class participated in
-    let courses: Vec<(_, _)> = results
-        .iter()
-        .map(|result| (result.event_id, result.category_name.to_owned()))
-        .unique()
-        .collect();
-    let mut stmt = conn.prepare(
-        "
-        select Result.time
-        from Result
-        where Result.event_id = ? and Result.category_name = ?
-        order by Result.time asc
-        limit 1
-    ",
-    )?;
-    let mut fastest_times = HashMap::new();
-    for (event_id, category_name) in courses {
-        let fastest_time: NaiveTime =
-            stmt.query_row(params![event_id, category_name], |row| row.get(0))?;
-        fastest_times.insert((event_id, category_name), total_seconds(fastest_time));
-    }
+    // Find the reference time of the season policy in all courses
+    // that someone of the given age class participated in
+    let fastest_times = reference_times(&conn, &cup, season, &age_class, &results, context)?;
 
     // Calculate score for each performance based on the fastest times
     let results = results.into_iter().map(|result| {
@@ -114,6 +115,14 @@ pub(crate) fn calculate_ranking(
             / total_seconds(result.time);
         Performance { score, ..result }
     });
+    let mut results: Vec<Performance> = results.collect();
+    context.explain_scores(&age_class, &fastest_times, &results);
+
+    // Results in a shorter course, depending on the policy of the season
+    results.extend(wrong_course_performances(
+        &conn, &cup, season, &age_class, context,
+    )?);
+    results.sort_by(|a, b| a.name.cmp(&b.name));
 
     // Calculate the total scores per runner
     let mut ranking: Vec<RankingEntry> = Vec::new();
@@ -133,6 +142,8 @@ pub(crate) fn calculate_ranking(
                 event_id: performance.event_id,
                 score: Some(performance.score),
                 place: Some(performance.position),
+                compensation: false,
+                counted: false,
             })
             .collect();
 
@@ -153,6 +164,8 @@ pub(crate) fn calculate_ranking(
                             event_id,
                             score: None,
                             place: None,
+                            compensation: false,
+                            counted: false,
                         })
                 })
                 .collect(),
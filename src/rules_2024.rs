@@ -10,7 +10,9 @@ use regex::Regex;
 use rusqlite::{params, Connection};
 
 use crate::{
-    db::Database, total_seconds, Performance, RankingEntry, RankingScore, COURSES_NUMBERED,
+    class_changes::ClassChanges, db::Database, reference_course_performances, reference_times,
+    total_seconds, wrong_course_performances, Performance, RankingContext, RankingEntry,
+    RankingScore, COURSES_NUMBERED,
 };
 
 #[derive(Debug)]
@@ -74,37 +76,57 @@ pub(crate) fn calculate_ranking(
     season: i16,
     age_class: String,
     events_count: usize,
+    context: &mut RankingContext,
 ) -> Result<Vec<RankingEntry>, anyhow::Error> {
     let conn = db.open()?;
 
     // Find all events
-    let mut stmt =
-        conn.prepare("select id from Event where cup = ? and season = ? order by date asc")?;
+    let mut stmt = conn.prepare(
+        "
+        select id from Event
+        where cup = ? and season = ?
+          and julianday(date) <= julianday(coalesce(?, date))
+        order by date asc
+    ",
+    )?;
     let events: Vec<_> = stmt
-        .query_map(params![cup, season], |row| {
+        .query_map(params![cup, season, context.until], |row| {
             let event_id: i64 = row.get(0)?;
             Ok(event_id)
         })?
         .filter_map(|event_id| event_id.ok())
         .collect();
 
-    let mut results = calculate_performances(&conn, &cup, season, &age_class)?;
+    let mut results = calculate_performances(&conn, &cup, season, &age_class, context)?;
+
+    // Results of runners in the classes they ran before changing to this class
+    let class_changes = ClassChanges::find(&conn, &cup, season, &age_class, context.until)?;
+    for earlier_class in class_changes.earlier_age_classes() {
+        for performance in calculate_performances(&conn, &cup, season, &earlier_class, context)? {
+            if class_changes.carries_over(&performance) {
+                context.explain_class_change(&performance);
+                results.push(performance);
+            }
+        }
+    }
 
-    // Handle people changing class mid-season (only for forest cup)
+    // Handle people changing class mid-season without a class history (only for forest cup)
     if cup == "forest-cup" {
         // Find the previous class and calculate that ranking
         if let Some(other_class) = find_previous_age_class(&age_class as &str) {
             let older_performances =
-                calculate_performances(&conn, &cup, season, &other_class.from_class)?;
+                calculate_performances(&conn, &cup, season, &other_class.from_class, context)?;
             // Add all older performances of runners in the real results
             let all_runners: HashSet<String> = results.iter().map(|p| p.name.clone()).collect();
 
             // only keep performances in a different course while in a different age class
             for performance in older_performances {
                 if all_runners.contains(&performance.name)
+                    && !class_changes.has_runner(&performance.name)
                     && performance.category_name == other_class.course
                     && performance.age_class == other_class.from_class
                 {
+                    context.explain_class_change(&performance);
                     results.push(performance);
                 }
             }
@@ -126,6 +148,14 @@ pub(crate) fn calculate_ranking(
         // }
     }
 
+    // Results in a shorter course, depending on the policy of the season
+    results.extend(wrong_course_performances(
+        &conn, &cup, season, &age_class, context,
+    )?);
+
+    // Only events up to the requested moment count
+    results.retain(|result| events.contains(&result.event_id));
+
     // Calculate the total scores per runner
     let mut ranking: Vec<RankingEntry> = Vec::new();
     for (name, runner_results) in &results
@@ -158,6 +188,8 @@ pub(crate) fn calculate_ranking(
                 event_id: performance.event_id,
                 score: Some(performance.score),
                 place: Some(performance.position),
+                compensation: false,
+                counted: false,
             })
             .collect();
 
@@ -178,6 +210,8 @@ pub(crate) fn calculate_ranking(
                             event_id,
                             score: None,
                             place: None,
+                            compensation: false,
+                            counted: false,
                         })
                 })
                 .collect(),
@@ -197,6 +231,7 @@ fn calculate_performances(
     cup: &str,
     season: i16,
     age_class: &str,
+    context: &mut RankingContext,
 ) -> anyhow::Result<Vec<Performance>> {
     let (age_class, course) = get_course(age_class)?;
     let performance_filter = PerformanceFilter::new(age_class.clone());
@@ -206,7 +241,7 @@ fn calculate_performances(
         "
         select
             Runner.name,
-            Runner.club,
+            Result.club,
             Event.id,
             Result.age_class,
             Result.category_name,
@@ -217,6 +252,7 @@ fn calculate_performances(
         where Event.cup = ?
           and Event.season = ?
           and Result.category_name = ?
+          and not Result.wrong_course
         order by Runner.name asc, Event.date asc
     ",
     )?;
@@ -235,63 +271,20 @@ fn calculate_performances(
             })
         })?
         .filter_map(|r| r.ok())
-        .filter(|r| !performance_filter.should_ignore(&r.age_class))
+        .filter(|r| {
+            let ignored = performance_filter.should_ignore(&r.age_class);
+            context.explain_filter(&age_class, r, ignored);
+            !ignored
+        })
         .collect();
 
-    // For D21, add all participants in course 1 (points are calculated relative to the men)
-    if age_class == "D21" {
-        let mut stmt = conn.prepare(
-            "
-            select
-                Runner.name,
-                Runner.club,
-                Event.id,
-                Result.age_class,
-                Result.position,
-                Result.time
-            from Result join Runner on Result.runner_id = Runner.id
-                        join Event on Result.event_id = Event.id
-            where Event.cup = ?
-              and Event.season = ?
-              and (Result.category_name = ? or Result.category_name = ?)
-            order by Runner.name asc, Event.date asc
-        ",
-        )?;
-        let course_01_results: Vec<Performance> = stmt
-            .query_map(params![cup, season, "D:01", "H:01"], |row| {
-                let event_id = row.get(2)?;
-                Ok(Performance {
-                    name: row.get(0)?,
-                    club: row.get(1)?,
-                    event_id,
-                    age_class: row.get(3)?,
-                    category_name: "D:01".to_owned(),
-                    position: row.get(4)?,
-                    time: row.get(5)?,
-                    score: 0,
-                })
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
-        results.extend(course_01_results);
-    }
+    // Results in courses that are scored against a reference course, e.g. women against the men
+    results.extend(reference_course_performances(
+        conn, cup, season, &age_class, &course, context,
+    )?);
 
-    // Find the fastest time
-    let mut fastest_times = HashMap::new();
-    for result in &results {
-        let course = (result.event_id, result.category_name.to_owned());
-        let result_seconds = total_seconds(result.time);
-        match fastest_times.get(&course) {
-            Some(fastest_time) => {
-                if result_seconds < *fastest_time {
-                    fastest_times.insert(course, result_seconds);
-                }
-            }
-            None => {
-                fastest_times.insert(course, result_seconds);
-            }
-        }
-    }
+    // Find the reference time of the season policy
+    let fastest_times = reference_times(conn, cup, season, &age_class, &results, context)?;
 
     // Calculate score for each performance based on the fastest times
     let results = results.into_iter().map(|result| {
@@ -302,9 +295,11 @@ fn calculate_performances(
             / total_seconds(result.time);
         Performance { score, ..result }
     });
-    Ok(results
+    let results: Vec<Performance> = results
         .filter(|result| result.age_class.chars().next() == age_class.chars().next()) // same gender
-        .collect())
+        .collect();
+    context.explain_scores(&age_class, &fastest_times, &results);
+    Ok(results)
 }
 
 fn get_course(age_class: &str) -> anyhow::Result<(String, String)> {
@@ -0,0 +1,504 @@
+// Season definitions
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{borrow::Cow, str::FromStr};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::{combined::find_combined_cup, rule_version, RuleVersion};
+
+/// Number of results that count when a season is not defined.
+const DEFAULT_EVENTS_COUNT: usize = 4;
+
+#[derive(Debug)]
+pub struct Season {
+    pub cup: &'static str,
+    pub season: i16,
+    /// Number of results that count for the ranking.
+    pub events_count: usize,
+    /// Points for runners who organised an event instead of running it.
+    pub compensation: CompensationPolicy,
+    /// How results in a shorter course than the class should run are ranked.
+    pub wrong_course: WrongCoursePolicy,
+    /// Who sets the reference time of a course.
+    pub reference: ReferencePolicy,
+    /// Courses of categories that are scored against another course.
+    pub reference_courses: &'static [ReferenceCourse],
+    /// Who gets a place in the ranking, other runners are guests.
+    pub eligibility: Eligibility,
+}
+
+/// Points for organisers of an event, who could not run it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompensationPolicy {
+    /// Organisers get no points.
+    #[default]
+    None,
+    /// The average of the counted results, for at most this many organised events.
+    Average(usize),
+}
+
+impl CompensationPolicy {
+    /// Number of organised events that are compensated.
+    pub fn events(&self) -> usize {
+        match self {
+            CompensationPolicy::None => 0,
+            CompensationPolicy::Average(events) => *events,
+        }
+    }
+
+    /// Points for an organised event given the scores of the runner, if any.
+    pub fn score(&self, scores: &[u32], events_count: usize) -> Option<u32> {
+        match self {
+            CompensationPolicy::None => None,
+            CompensationPolicy::Average(_) => {
+                let counted: Vec<u32> = scores
+                    .iter()
+                    .copied()
+                    .sorted_unstable()
+                    .rev()
+                    .take(events_count)
+                    .collect();
+                if counted.is_empty() {
+                    return None;
+                }
+                Some(counted.iter().sum::<u32>() / counted.len() as u32)
+            }
+        }
+    }
+}
+
+/// Requirements to be ranked, checked against the club of a runner and the registry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Eligibility {
+    /// Member of an Orienteering Vlaanderen club.
+    pub ov_club: bool,
+    /// Member of this federation.
+    pub federation: Option<&'static str>,
+    /// Of this nationality.
+    pub nationality: Option<&'static str>,
+    /// Holds a licence.
+    pub licence: bool,
+}
+
+impl Eligibility {
+    /// Everyone with a result is ranked.
+    pub const OPEN: Eligibility = Eligibility {
+        ov_club: false,
+        federation: None,
+        nationality: None,
+        licence: false,
+    };
+}
+
+/// The runners whose fastest time is the reference for the scores on a course.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferencePolicy {
+    /// Everyone who finished the course.
+    AllFinishers,
+    /// The runners ranked in the category, after the rules left out other classes.
+    EligibleRunners,
+    /// Everyone who finished the course and meets the eligibility of the season.
+    EligibleFinishers,
+    /// Finishers of the course of the same gender as the category.
+    SameGender,
+}
+
+impl std::fmt::Display for ReferencePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferencePolicy::AllFinishers => write!(f, "all"),
+            ReferencePolicy::EligibleRunners => write!(f, "eligible"),
+            ReferencePolicy::EligibleFinishers => write!(f, "eligible-finishers"),
+            ReferencePolicy::SameGender => write!(f, "gender"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown reference policy, valid policies are: all, eligible, eligible-finishers, gender")]
+pub struct UnknownReferencePolicy;
+
+impl FromStr for ReferencePolicy {
+    type Err = UnknownReferencePolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(ReferencePolicy::AllFinishers),
+            "eligible" => Ok(ReferencePolicy::EligibleRunners),
+            "eligible-finishers" => Ok(ReferencePolicy::EligibleFinishers),
+            "gender" => Ok(ReferencePolicy::SameGender),
+            _ => Err(UnknownReferencePolicy),
+        }
+    }
+}
+
+/// A course whose results count for a category, scored against the finishers of another course
+/// too, e.g. the women of course 1 against the men.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferenceCourse {
+    pub age_class: Cow<'static, str>,
+    /// Course whose results count for the category, besides the course of the category.
+    pub course: Cow<'static, str>,
+    /// Its fastest finisher is the reference time of `course` when they are faster.
+    pub reference_course: Cow<'static, str>,
+}
+
+/// Women of course 1 are scored against the men of course 1 from the 2024 rules.
+const WOMEN_IN_COURSE_1: &[ReferenceCourse] = &[ReferenceCourse {
+    age_class: Cow::Borrowed("D21"),
+    course: Cow::Borrowed("D:01"),
+    reference_course: Cow::Borrowed("H:01"),
+}];
+
+impl std::fmt::Display for ReferenceCourse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}={}",
+            self.age_class, self.course, self.reference_course
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown reference course, it should be <class>:<course>=<reference course>")]
+pub struct InvalidReferenceCourse;
+
+impl FromStr for ReferenceCourse {
+    type Err = InvalidReferenceCourse;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (category, reference_course) = s.split_once('=').ok_or(InvalidReferenceCourse)?;
+        let (age_class, course) = category.split_once(':').ok_or(InvalidReferenceCourse)?;
+        if [age_class, course, reference_course]
+            .iter()
+            .any(|part| part.is_empty())
+        {
+            return Err(InvalidReferenceCourse);
+        }
+        Ok(ReferenceCourse {
+            age_class: Cow::Owned(age_class.to_owned()),
+            course: Cow::Owned(course.to_owned()),
+            reference_course: Cow::Owned(reference_course.to_owned()),
+        })
+    }
+}
+
+/// How a result is ranked when the runner ran a shorter course than their class should run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WrongCoursePolicy {
+    /// The result does not count.
+    #[default]
+    Exclude,
+    /// The result counts with zero points.
+    ScoreZero,
+    /// The result counts with a percentage of its score.
+    Penalty(u32),
+    /// The result counts like any other, the loader warns about it.
+    Allow,
+}
+
+impl WrongCoursePolicy {
+    /// Score of a result in a shorter course, `None` when it does not count.
+    pub fn apply(&self, score: u32) -> Option<u32> {
+        match self {
+            WrongCoursePolicy::Exclude => None,
+            WrongCoursePolicy::ScoreZero => Some(0),
+            WrongCoursePolicy::Penalty(percent) => Some(score * percent / 100),
+            WrongCoursePolicy::Allow => Some(score),
+        }
+    }
+}
+
+impl std::fmt::Display for WrongCoursePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WrongCoursePolicy::Exclude => write!(f, "exclude"),
+            WrongCoursePolicy::ScoreZero => write!(f, "zero"),
+            WrongCoursePolicy::Penalty(percent) => write!(f, "penalty:{}", percent),
+            WrongCoursePolicy::Allow => write!(f, "allow"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Unknown wrong course policy, valid policies are: exclude, zero, penalty:<percent>, allow")]
+pub struct UnknownWrongCoursePolicy;
+
+impl FromStr for WrongCoursePolicy {
+    type Err = UnknownWrongCoursePolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exclude" => Ok(WrongCoursePolicy::Exclude),
+            "zero" => Ok(WrongCoursePolicy::ScoreZero),
+            "allow" => Ok(WrongCoursePolicy::Allow),
+            _ => match s.strip_prefix("penalty:").map(str::parse) {
+                Some(Ok(percent)) if percent <= 100 => Ok(WrongCoursePolicy::Penalty(percent)),
+                _ => Err(UnknownWrongCoursePolicy),
+            },
+        }
+    }
+}
+
+pub static SEASONS: &[Season] = &[
+    Season {
+        cup: "forest-cup",
+        season: 2022,
+        events_count: 3,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::AllFinishers,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "forest-cup",
+        season: 2023,
+        events_count: 4,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::AllFinishers,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "forest-cup",
+        season: 2024,
+        events_count: 3,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "forest-cup",
+        season: 2025,
+        events_count: 4,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: WOMEN_IN_COURSE_1,
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "forest-cup",
+        season: 2026,
+        events_count: 4,
+        compensation: CompensationPolicy::Average(1),
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "city-cup",
+        season: 2022,
+        events_count: 4,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::AllFinishers,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "city-cup",
+        season: 2023,
+        events_count: 4,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: &[],
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "city-cup",
+        season: 2024,
+        events_count: 3,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: WOMEN_IN_COURSE_1,
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "city-cup",
+        season: 2025,
+        events_count: 4,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleRunners,
+        reference_courses: WOMEN_IN_COURSE_1,
+        eligibility: Eligibility::OPEN,
+    },
+    Season {
+        cup: "kampioen",
+        season: 2022,
+        events_count: 3,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleFinishers,
+        reference_courses: &[],
+        eligibility: Eligibility {
+            ov_club: true,
+            ..Eligibility::OPEN
+        },
+    },
+    Season {
+        cup: "kampioen",
+        season: 2023,
+        events_count: 2,
+        compensation: CompensationPolicy::None,
+        wrong_course: WrongCoursePolicy::Exclude,
+        reference: ReferencePolicy::EligibleFinishers,
+        reference_courses: &[],
+        eligibility: Eligibility {
+            ov_club: true,
+            ..Eligibility::OPEN
+        },
+    },
+];
+
+pub fn find_season(cup: &str, season: i16) -> Option<&'static Season> {
+    SEASONS
+        .iter()
+        .find(|definition| definition.cup == cup && definition.season == season)
+}
+
+/// Number of results that count for the ranking of a season.
+pub fn events_count(cup: &str, season: i16) -> usize {
+    if let Some(combined_cup) = find_combined_cup(cup, season) {
+        return combined_cup
+            .cups
+            .iter()
+            .map(|contributing_cup| contributing_cup.events_count)
+            .sum();
+    }
+    find_season(cup, season).map_or(DEFAULT_EVENTS_COUNT, |definition| definition.events_count)
+}
+
+/// Points for organisers in a season.
+///
+/// Seasons that are not defined compensate one organised event from the 2026 rules.
+pub fn compensation_policy(cup: &str, season: i16) -> CompensationPolicy {
+    match find_season(cup, season) {
+        Some(definition) => definition.compensation,
+        None => match rule_version(cup, season) {
+            RuleVersion::Rules2026 => CompensationPolicy::Average(1),
+            _ => CompensationPolicy::None,
+        },
+    }
+}
+
+/// How results in a shorter course are ranked in a season, they don't count when it is not defined.
+pub fn wrong_course_policy(cup: &str, season: i16) -> WrongCoursePolicy {
+    find_season(cup, season).map_or(WrongCoursePolicy::default(), |definition| {
+        definition.wrong_course
+    })
+}
+
+/// Who sets the reference time of a course in a season.
+///
+/// Seasons that are not defined use the population of their rules:
+/// all finishers with the 2022 rules, the ranked runners since.
+/// The Flemish championship uses the eligible finishers, as only members of OV clubs were
+/// stored before guests were kept.
+pub fn reference_policy(cup: &str, season: i16) -> ReferencePolicy {
+    match find_season(cup, season) {
+        Some(definition) => definition.reference,
+        None if cup == "kampioen" => ReferencePolicy::EligibleFinishers,
+        None => match rule_version(cup, season) {
+            RuleVersion::Rules2022 => ReferencePolicy::AllFinishers,
+            _ => ReferencePolicy::EligibleRunners,
+        },
+    }
+}
+
+/// Courses that are scored against another course in a season.
+///
+/// Seasons that are not defined score the women of course 1 against the men with the 2024 rules.
+pub fn reference_courses(cup: &str, season: i16) -> &'static [ReferenceCourse] {
+    match find_season(cup, season) {
+        Some(definition) => definition.reference_courses,
+        None => match rule_version(cup, season) {
+            RuleVersion::Rules2024 => WOMEN_IN_COURSE_1,
+            _ => &[],
+        },
+    }
+}
+
+/// Who gets a place in the ranking of a season.
+///
+/// When the season is not defined, the Flemish championship ranks the members of
+/// Orienteering Vlaanderen clubs and the other cups rank everyone.
+pub fn eligibility(cup: &str, season: i16) -> Eligibility {
+    match find_season(cup, season) {
+        Some(definition) => definition.eligibility,
+        None if cup == "kampioen" => Eligibility {
+            ov_club: true,
+            ..Eligibility::OPEN
+        },
+        None => Eligibility::OPEN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompensationPolicy, ReferenceCourse, ReferencePolicy, WrongCoursePolicy};
+
+    #[test]
+    fn compensation() {
+        let average = CompensationPolicy::Average(1);
+        assert_eq!(average.score(&[], 4), None);
+        assert_eq!(average.score(&[900, 800], 4), Some(850));
+        assert_eq!(average.score(&[500, 1000, 900, 800, 700], 4), Some(850));
+        assert_eq!(CompensationPolicy::None.score(&[900, 800], 4), None);
+    }
+
+    #[test]
+    fn wrong_course_policies() {
+        assert_eq!(
+            "exclude".parse::<WrongCoursePolicy>().unwrap().apply(900),
+            None
+        );
+        assert_eq!(
+            "zero".parse::<WrongCoursePolicy>().unwrap().apply(900),
+            Some(0)
+        );
+        assert_eq!(
+            "penalty:80"
+                .parse::<WrongCoursePolicy>()
+                .unwrap()
+                .apply(900),
+            Some(720)
+        );
+        assert_eq!(
+            "allow".parse::<WrongCoursePolicy>().unwrap().apply(900),
+            Some(900)
+        );
+        assert!("penalty:120".parse::<WrongCoursePolicy>().is_err());
+        assert!("penalty".parse::<WrongCoursePolicy>().is_err());
+    }
+
+    #[test]
+    fn reference_policies() {
+        assert_eq!(
+            "gender".parse::<ReferencePolicy>().unwrap(),
+            ReferencePolicy::SameGender
+        );
+        assert!("course:H:01".parse::<ReferencePolicy>().is_err());
+        assert!("fastest".parse::<ReferencePolicy>().is_err());
+
+        let course: ReferenceCourse = "D21:D:01=H:01".parse().unwrap();
+        assert_eq!(course.age_class, "D21");
+        assert_eq!(course.course, "D:01");
+        assert_eq!(course.reference_course, "H:01");
+        assert_eq!(course.to_string(), "D21:D:01=H:01");
+        assert!("D21:D:01".parse::<ReferenceCourse>().is_err());
+        assert!("D21=H:01".parse::<ReferenceCourse>().is_err());
+    }
+}
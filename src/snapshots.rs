@@ -0,0 +1,381 @@
+// Official rankings frozen when a season is closed
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::bail;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use crate::{
+    calculate_ranking, calculate_ranking_with,
+    db::Database,
+    diff::{diff_rankings, RankingChange},
+    seasons, RankingEntry, RankingOptions,
+};
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotInfo {
+    pub cup: String,
+    pub season: i16,
+    #[serde(rename = "ageClass")]
+    pub age_class: String,
+    pub created: DateTime<Utc>,
+    #[serde(rename = "eventsCount")]
+    pub events_count: usize,
+}
+
+/// Store the current ranking of a category as the official ranking.
+pub fn freeze_ranking(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+    replace: bool,
+) -> anyhow::Result<()> {
+    if !replace && frozen_ranking(db, cup, season, age_class)?.is_some() {
+        bail!(
+            "The {} {} ranking of {} is already frozen",
+            cup,
+            season,
+            age_class
+        );
+    }
+    let ranking = calculate_ranking(
+        db,
+        cup.to_owned(),
+        season,
+        age_class.to_owned(),
+        events_count,
+    )?;
+
+    let conn = db.open()?;
+    conn.execute(
+        "
+        insert into Snapshot (cup, season, age_class, created, data, events_count)
+        values (?, ?, ?, ?, ?, ?)
+        on conflict (cup, season, age_class) do update set
+            created = excluded.created,
+            data = excluded.data,
+            events_count = excluded.events_count
+    ",
+        params![
+            cup,
+            season,
+            age_class,
+            Utc::now(),
+            serde_json::to_string(&ranking)?,
+            events_count as i64
+        ],
+    )?;
+    Ok(())
+}
+
+/// The frozen ranking of a category when it was frozen with `events_count` counted events.
+pub fn find_snapshot(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+) -> anyhow::Result<Option<Vec<RankingEntry>>> {
+    Ok(frozen_ranking(db, cup, season, age_class)?
+        .filter(|(frozen_events_count, _)| *frozen_events_count == events_count)
+        .map(|(_, ranking)| ranking))
+}
+
+// The frozen ranking of a category with the number of events that counted
+fn frozen_ranking(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+) -> anyhow::Result<Option<(usize, Vec<RankingEntry>)>> {
+    let conn = db.open()?;
+    // Databases that were not loaded since snapshots were introduced
+    let has_snapshots: bool = conn.query_row(
+        "select count(*) > 0 from sqlite_master where type = 'table' and name = 'Snapshot'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_snapshots {
+        return Ok(None);
+    }
+    let snapshot: Option<(usize, String)> = conn
+        .query_row(
+            "
+            select coalesce(events_count, ?), data
+            from Snapshot
+            where cup = ? and season = ? and age_class = ?
+        ",
+            params![
+                seasons::events_count(cup, season) as i64,
+                cup,
+                season,
+                age_class
+            ],
+            |row| Ok((row.get::<_, i64>(0)? as usize, row.get(1)?)),
+        )
+        .optional()?;
+    match snapshot {
+        Some((events_count, data)) => Ok(Some((events_count, serde_json::from_str(&data)?))),
+        None => Ok(None),
+    }
+}
+
+pub fn list_snapshots(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+) -> anyhow::Result<Vec<SnapshotInfo>> {
+    let conn = db.open()?;
+    let mut stmt = conn.prepare(
+        "
+        select cup, season, age_class, created, coalesce(events_count, ?)
+        from Snapshot
+        where cup = ? and season = ?
+        order by age_class asc
+    ",
+    )?;
+    let snapshots = stmt
+        .query_map(
+            params![seasons::events_count(cup, season) as i64, cup, season],
+            |row| {
+                Ok(SnapshotInfo {
+                    cup: row.get(0)?,
+                    season: row.get(1)?,
+                    age_class: row.get(2)?,
+                    created: row.get(3)?,
+                    events_count: row.get::<_, i64>(4)? as usize,
+                })
+            },
+        )?
+        .collect::<Result<_, _>>()?;
+    Ok(snapshots)
+}
+
+/// Reopen a frozen ranking, it is calculated live again.
+pub fn remove_snapshot(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+) -> anyhow::Result<()> {
+    let conn = db.open()?;
+    let removed = conn.execute(
+        "delete from Snapshot where cup = ? and season = ? and age_class = ?",
+        params![cup, season, age_class],
+    )?;
+    if removed == 0 {
+        bail!(
+            "The {} {} ranking of {} is not frozen",
+            cup,
+            season,
+            age_class
+        );
+    }
+    Ok(())
+}
+
+/// The frozen ranking of a category when there is one for `events_count` counted events,
+/// the calculated ranking otherwise.
+pub fn official_ranking(
+    db: &dyn Database,
+    cup: String,
+    season: i16,
+    age_class: String,
+    events_count: usize,
+) -> anyhow::Result<Vec<RankingEntry>> {
+    match find_snapshot(db, &cup, season, &age_class, events_count)? {
+        Some(ranking) => Ok(ranking),
+        None => calculate_ranking(db, cup, season, age_class, events_count),
+    }
+}
+
+/// The official ranking when the options don't change the calculation, the calculated ranking otherwise.
+///
+/// Intermediate standings and rankings with other rules or policies are never frozen.
+pub fn official_ranking_with(
+    db: &dyn Database,
+    cup: String,
+    season: i16,
+    age_class: String,
+    events_count: usize,
+    options: &RankingOptions,
+) -> anyhow::Result<Vec<RankingEntry>> {
+    if *options == RankingOptions::default() {
+        official_ranking(db, cup, season, age_class, events_count)
+    } else {
+        calculate_ranking_with(db, cup, season, age_class, events_count, options)
+    }
+}
+
+/// Compare a frozen ranking with a fresh calculation.
+pub fn check_snapshot(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+) -> anyhow::Result<Vec<RankingChange>> {
+    let frozen = match frozen_ranking(db, cup, season, age_class)? {
+        Some((frozen_events_count, frozen)) if frozen_events_count == events_count => frozen,
+        Some((frozen_events_count, _)) => bail!(
+            "The {} {} ranking of {} is frozen with {} counted events, not {}",
+            cup,
+            season,
+            age_class,
+            frozen_events_count,
+            events_count
+        ),
+        None => bail!(
+            "The {} {} ranking of {} is not frozen",
+            cup,
+            season,
+            age_class
+        ),
+    };
+    let fresh = calculate_ranking(
+        db,
+        cup.to_owned(),
+        season,
+        age_class.to_owned(),
+        events_count,
+    )?;
+    Ok(diff_rankings(&frozen, &fresh))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rusqlite::params;
+
+    use crate::{
+        calculate_ranking,
+        db::Database,
+        export::{export_rankings, ExportFormat},
+        publish::publish,
+        testing::{add_event, add_result, database},
+        RankingOptions,
+    };
+
+    use super::{check_snapshot, freeze_ranking, official_ranking};
+
+    #[test]
+    fn frozen_for_its_events_count() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            event_id,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            event_id,
+            "B",
+            "Trol",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:15:00",
+        );
+        freeze_ranking(&db, "forest-cup", 2026, "H21", 4, false).unwrap();
+        db.open()
+            .unwrap()
+            .execute(
+                "update Result set time = ? where time = ?",
+                params!["00:50:00", "01:15:00"],
+            )
+            .unwrap();
+
+        let official = |events_count| {
+            official_ranking(
+                &db,
+                "forest-cup".to_owned(),
+                2026,
+                "H21".to_owned(),
+                events_count,
+            )
+            .unwrap()[0]
+                .name
+                .clone()
+        };
+        assert_eq!(official(4), "A");
+        // Another number of counted events is calculated, not frozen
+        assert_eq!(official(3), "B");
+        assert!(check_snapshot(&db, "forest-cup", 2026, "H21", 3).is_err());
+        assert_eq!(
+            check_snapshot(&db, "forest-cup", 2026, "H21", 4)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn frozen_output() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            event_id,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            event_id,
+            "B",
+            "Trol",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:15:00",
+        );
+        freeze_ranking(&db, "forest-cup", 2026, "H21", 4, false).unwrap();
+
+        let directory = std::env::temp_dir().join(format!("ov-cup-frozen-{}", std::process::id()));
+        let ranking_path = directory.join("forest-cup").join("2026").join("H21.json");
+        let age_classes = vec!["H21".to_owned()];
+        let export = || {
+            export_rankings(
+                &db,
+                "forest-cup",
+                2026,
+                &age_classes,
+                4,
+                &RankingOptions::default(),
+                ExportFormat::Csv,
+            )
+            .unwrap()
+        };
+        publish(&db, &directory).unwrap();
+        let published = fs::read(&ranking_path).unwrap();
+        let exported = export();
+
+        // A correction after the season was closed
+        db.open()
+            .unwrap()
+            .execute(
+                "update Result set time = ? where time = ?",
+                params!["00:50:00", "01:15:00"],
+            )
+            .unwrap();
+        let live =
+            calculate_ranking(&db, "forest-cup".to_owned(), 2026, "H21".to_owned(), 4).unwrap();
+        assert_eq!(live[0].name, "B");
+
+        publish(&db, &directory).unwrap();
+        assert_eq!(fs::read(&ranking_path).unwrap(), published);
+        assert_eq!(export(), exported);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
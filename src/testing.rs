@@ -0,0 +1,98 @@
+// Fixtures shared by the tests
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use chrono::NaiveDate;
+use rusqlite::params;
+
+use crate::{
+    create_database,
+    db::{Database, ScratchDatabase},
+    RankingEntry, RankingScore,
+};
+
+/// An empty database with all tables.
+pub(crate) fn database() -> ScratchDatabase {
+    let db = ScratchDatabase::new().unwrap();
+    create_database(&db).unwrap();
+    db
+}
+
+/// Add an event on a date (YYYY-MM-DD) and return its id.
+pub(crate) fn add_event(db: &dyn Database, cup: &str, season: i16, date: &str) -> i64 {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap()
+        .and_utc();
+    let conn = db.open().unwrap();
+    conn.execute(
+        "insert into Event (cup, season, name, location, date) values (?, ?, ?, '', ?)",
+        params![cup, season.to_string(), format!("{} {}", cup, date), date],
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
+
+/// Add the result of a runner in a course, with a time (HH:MM:SS).
+pub(crate) fn add_result(
+    db: &dyn Database,
+    event_id: i64,
+    name: &str,
+    club: &str,
+    course: &str,
+    age_class: &str,
+    time: &str,
+) {
+    let conn = db.open().unwrap();
+    conn.execute(
+        "
+        insert into Runner (name, club) values (?, ?)
+        on conflict (name) do update set club = excluded.club
+    ",
+        params![name, club],
+    )
+    .unwrap();
+    conn.execute(
+        "
+        insert into Result (event_id, runner_id, category_name, age_class, position, time, club)
+        select ?, id, ?, ?,
+            (
+                select count(*) + 1 from Result
+                where event_id = ? and category_name = ? and time < ?
+            ),
+            ?, ?
+        from Runner where name = ?
+    ",
+        params![event_id, course, age_class, event_id, course, time, time, club, name],
+    )
+    .unwrap();
+}
+
+/// A ranking entry without scores.
+pub(crate) fn entry(name: &str, total_score: u32) -> RankingEntry {
+    RankingEntry {
+        name: name.to_owned(),
+        club: String::new(),
+        total_score,
+        scores: vec![],
+    }
+}
+
+/// A ranking entry with a score in every event, the best `events_count` make up the total.
+pub(crate) fn scored_entry(name: &str, scores: &[u32], events_count: usize) -> RankingEntry {
+    let mut sorted = scores.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    RankingEntry {
+        total_score: sorted.iter().take(events_count).sum(),
+        scores: scores
+            .iter()
+            .enumerate()
+            .map(|(event_id, &score)| RankingScore {
+                score: Some(score),
+                ..RankingScore::missing(event_id as i64)
+            })
+            .collect(),
+        ..entry(name, 0)
+    }
+}
@@ -0,0 +1,485 @@
+// Rankings with a hypothetical result
+// SPDX-FileCopyrightText: 2026 Jeroen Hoekx
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use anyhow::bail;
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension, Params};
+
+use crate::{
+    calculate_ranking,
+    combined::find_combined_cup,
+    create_database,
+    db::{Database, ScratchDatabase},
+    explain::explain_ranking,
+    total_seconds, RankingEntry, RankingOptions,
+};
+
+/// Highest score of a runner in an event.
+const MAX_SCORE: u32 = 1000;
+
+/// Winner of a course without other results, sets the time a hypothetical score is relative to.
+const PACER: &str = "What-if pacer";
+const PACER_SECONDS: u32 = 3600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HypotheticalResult {
+    /// Points in the event, at most 1000.
+    Score(u32),
+    /// Running time, scored against the other results of the course.
+    Time(NaiveTime),
+}
+
+#[derive(Debug)]
+pub struct WhatIf {
+    pub name: String,
+    /// An event of the season, or `None` for the next event.
+    pub event_id: Option<i64>,
+    pub result: HypotheticalResult,
+}
+
+/// The ranking of a category when a runner gets a hypothetical result.
+///
+/// The result replaces the result of the runner in the event, if any.
+/// The results of the season are copied in memory, so the database is not changed.
+pub fn what_if_ranking(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    events_count: usize,
+    what_if: &WhatIf,
+) -> anyhow::Result<Vec<RankingEntry>> {
+    if let HypotheticalResult::Score(score) = what_if.result {
+        if score == 0 || score > MAX_SCORE {
+            bail!("A score should be between 1 and {}", MAX_SCORE);
+        }
+    }
+
+    let cups: Vec<&str> = match find_combined_cup(cup, season) {
+        Some(combined_cup) => combined_cup.cups.iter().map(|cup| cup.cup).collect(),
+        None => vec![cup],
+    };
+    let scratch = copy_season(db, &cups, season)?;
+    let conn = scratch.open()?;
+    let runner_id = find_runner(&conn, &what_if.name)?;
+    let event_id = match what_if.event_id {
+        Some(event_id) => event_id,
+        None => add_next_event(&conn, cup, season)?,
+    };
+    // The cup that organised the event, which differs from a combined cup
+    let event_cup: Option<String> = conn
+        .query_row(
+            "select cup from Event where id = ?",
+            params![event_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(event_cup) = event_cup else {
+        bail!("Event {} does not exist in {} {}", event_id, cup, season);
+    };
+    let course = find_course(&conn, &event_cup, season, age_class, runner_id, event_id)?;
+    conn.execute(
+        "delete from Result where event_id = ? and runner_id = ?",
+        params![event_id, runner_id],
+    )?;
+
+    let mut pacer = false;
+    let seconds = match what_if.result {
+        HypotheticalResult::Time(time) => total_seconds(time),
+        HypotheticalResult::Score(score) => {
+            // Relative to the reference time the ranking scores the course with
+            let reference_time = |db: &dyn Database| {
+                reference_time(db, &event_cup, season, age_class, event_id, &course)
+            };
+            let reference = match reference_time(&scratch)? {
+                Some(reference) => reference,
+                None => {
+                    pacer = true;
+                    let pacer_id = find_runner(&conn, PACER)?;
+                    insert_result(&conn, event_id, pacer_id, &course, age_class, PACER_SECONDS)?;
+                    reference_time(&scratch)?.unwrap_or(PACER_SECONDS)
+                }
+            };
+            // The slowest time that still scores the requested points, rankings floor scores.
+            // With a reference over 1000 seconds every score has such a time.
+            MAX_SCORE * reference / score
+        }
+    };
+    insert_result(&conn, event_id, runner_id, &course, age_class, seconds)?;
+
+    let mut ranking = calculate_ranking(
+        &scratch,
+        cup.to_owned(),
+        season,
+        age_class.to_owned(),
+        events_count,
+    )?;
+    if pacer {
+        ranking.retain(|entry| entry.name != PACER);
+    }
+    Ok(ranking)
+}
+
+/// A database with the events, results and runners of a season of some cups.
+fn copy_season(db: &dyn Database, cups: &[&str], season: i16) -> anyhow::Result<ScratchDatabase> {
+    let scratch = ScratchDatabase::new()?;
+    create_database(&scratch)?;
+    let source = db.open()?;
+    let target = scratch.open()?;
+
+    let events = "select id from Event where cup = ?1 and season = ?2";
+    let runners = format!(
+        "
+        select runner_id from Result where event_id in ({events})
+        union select runner_id from Organiser where event_id in ({events})
+        union select runner_id from ClassChange where cup = ?1 and season = ?2
+    "
+    );
+    for cup in cups {
+        for (table, condition) in [
+            ("Event", "cup = ?1 and season = ?2".to_owned()),
+            ("Runner", format!("id in ({runners})")),
+            ("Registration", format!("runner_id in ({runners})")),
+            ("Course", format!("event_id in ({events})")),
+            ("Result", format!("event_id in ({events})")),
+            ("Organiser", format!("event_id in ({events})")),
+            ("ClassChange", "cup = ?1 and season = ?2".to_owned()),
+        ] {
+            copy_rows(&source, &target, table, &condition, params![cup, season])?;
+        }
+    }
+    Ok(scratch)
+}
+
+fn copy_rows(
+    source: &Connection,
+    target: &Connection,
+    table: &str,
+    condition: &str,
+    params: impl Params,
+) -> anyhow::Result<()> {
+    let mut select = source.prepare(&format!("select * from {table} where {condition}"))?;
+    let columns: Vec<String> = select
+        .column_names()
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    let mut insert = target.prepare(&format!(
+        "insert or ignore into {table} ({}) values ({})",
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    ))?;
+    let mut rows = select.query(params)?;
+    while let Some(row) = rows.next()? {
+        let values = (0..columns.len())
+            .map(|index| row.get::<_, Value>(index))
+            .collect::<Result<Vec<_>, _>>()?;
+        insert.execute(params_from_iter(values))?;
+    }
+    Ok(())
+}
+
+// The reference time of the course the ranking of the category uses
+fn reference_time(
+    db: &dyn Database,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    event_id: i64,
+    course: &str,
+) -> anyhow::Result<Option<u32>> {
+    let explanation = explain_ranking(db, cup, season, age_class, 1, &RankingOptions::default())?;
+    Ok(explanation
+        .courses
+        .iter()
+        .filter(|explanation| {
+            explanation.event_id == event_id
+                && explanation.course == course
+                && explanation.ranked_as == age_class
+        })
+        .find_map(|explanation| explanation.fastest_time)
+        .map(total_seconds))
+}
+
+fn find_runner(conn: &Connection, name: &str) -> anyhow::Result<i64> {
+    conn.execute(
+        "insert into Runner (name, club) values (?, '') on conflict (name) do nothing",
+        params![name],
+    )?;
+    Ok(conn.query_row(
+        "select id from Runner where name = ?",
+        params![name],
+        |row| row.get(0),
+    )?)
+}
+
+// An event on the day after the last event of the season
+fn add_next_event(conn: &Connection, cup: &str, season: i16) -> anyhow::Result<i64> {
+    if find_combined_cup(cup, season).is_some() {
+        bail!("The next event of {} belongs to one of its cups", cup);
+    }
+    let last_date: Option<DateTime<Utc>> = conn.query_row(
+        "select max(date) from Event where cup = ? and cast(season as integer) = ?",
+        params![cup, season],
+        |row| row.get(0),
+    )?;
+    let date = last_date.unwrap_or_else(Utc::now) + TimeDelta::days(1);
+    conn.execute(
+        "insert into Event (cup, season, name, location, date) values (?, ?, ?, '', ?)",
+        params![cup, season.to_string(), "Next event", date],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// The course of the runner in the event, or else the course the category usually runs
+fn find_course(
+    conn: &Connection,
+    cup: &str,
+    season: i16,
+    age_class: &str,
+    runner_id: i64,
+    event_id: i64,
+) -> anyhow::Result<String> {
+    let course: Option<String> = conn
+        .query_row(
+            "select category_name from Result where event_id = ? and runner_id = ?",
+            params![event_id, runner_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(course) = course {
+        return Ok(course);
+    }
+    let course: Option<String> = conn
+        .query_row(
+            "
+            select Result.category_name
+            from Result join Event on Result.event_id = Event.id
+            where Event.cup = ? and cast(Event.season as integer) = ?
+              and Result.age_class = ?
+            group by Result.category_name
+            order by count(*) desc
+            limit 1
+        ",
+            params![cup, season, age_class],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match course {
+        Some(course) => Ok(course),
+        None => bail!("No course found for {} in {} {}", age_class, cup, season),
+    }
+}
+
+fn insert_result(
+    conn: &Connection,
+    event_id: i64,
+    runner_id: i64,
+    course: &str,
+    age_class: &str,
+    seconds: u32,
+) -> anyhow::Result<()> {
+    let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)
+        .ok_or_else(|| anyhow::anyhow!("A time should be less than a day"))?;
+    let position: u32 = conn.query_row(
+        "select count(*) + 1 from Result where event_id = ? and category_name = ? and time < ?",
+        params![event_id, course, time],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "
+        insert into Result (event_id, runner_id, category_name, age_class, position, time, club)
+        select ?, id, ?, ?, ?, ?, club from Runner where id = ?
+    ",
+        params![event_id, course, age_class, position, time, runner_id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::Database,
+        testing::{add_event, add_result, database},
+        RankingEntry,
+    };
+
+    use super::{what_if_ranking, HypotheticalResult, WhatIf};
+
+    fn count(db: &dyn Database, table: &str) -> u32 {
+        db.open()
+            .unwrap()
+            .query_row(&format!("select count(*) from {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    fn find<'a>(ranking: &'a [RankingEntry], name: &str) -> &'a RankingEntry {
+        ranking.iter().find(|entry| entry.name == name).unwrap()
+    }
+
+    #[test]
+    fn score_in_an_event() {
+        let db = database();
+        let first = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        let second = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            first,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            first,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:50:00",
+        );
+        add_result(
+            &db,
+            second,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+
+        let what_if = WhatIf {
+            name: "A".to_owned(),
+            event_id: Some(first),
+            result: HypotheticalResult::Score(900),
+        };
+        let ranking = what_if_ranking(&db, "forest-cup", 2026, "H21", 4, &what_if).unwrap();
+        let entry = find(&ranking, "A");
+        assert_eq!(entry.scores[0].score, Some(900));
+        assert_eq!(entry.total_score, 1900);
+        assert_eq!(find(&ranking, "B").total_score, 1000);
+
+        assert_eq!(count(&db, "Result"), 3);
+        let time: String = db
+            .open()
+            .unwrap()
+            .query_row(
+                "
+                select time from Result join Runner on Result.runner_id = Runner.id
+                where event_id = ? and name = 'A'
+            ",
+                [first],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(time, "01:00:00");
+    }
+
+    #[test]
+    fn requested_scores_are_ranked() {
+        let db = database();
+        let event_id = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        add_result(
+            &db,
+            event_id,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:10:00",
+        );
+        add_result(
+            &db,
+            event_id,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:58:17",
+        );
+
+        for score in [41, 333, 667, 750, 901, 998, 999, 1000] {
+            let what_if = WhatIf {
+                name: "A".to_owned(),
+                event_id: Some(event_id),
+                result: HypotheticalResult::Score(score),
+            };
+            let ranking = what_if_ranking(&db, "forest-cup", 2026, "H21", 4, &what_if).unwrap();
+            assert_eq!(find(&ranking, "A").scores[0].score, Some(score));
+        }
+    }
+
+    #[test]
+    fn score_in_the_next_event() {
+        let db = database();
+        let first = add_event(&db, "forest-cup", 2026, "2026-01-10");
+        let second = add_event(&db, "forest-cup", 2026, "2026-01-17");
+        add_result(
+            &db,
+            first,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+        add_result(
+            &db,
+            first,
+            "B",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "00:50:00",
+        );
+        add_result(
+            &db,
+            second,
+            "A",
+            "Omega",
+            "H:Zwart Extra Lang",
+            "H21",
+            "01:00:00",
+        );
+
+        let what_if = WhatIf {
+            name: "A".to_owned(),
+            event_id: None,
+            result: HypotheticalResult::Score(800),
+        };
+        let ranking = what_if_ranking(&db, "forest-cup", 2026, "H21", 4, &what_if).unwrap();
+        assert_eq!(ranking.len(), 2);
+        let entry = find(&ranking, "A");
+        assert_eq!(entry.scores.len(), 3);
+        assert_eq!(entry.scores[2].score, Some(800));
+        assert_eq!(entry.total_score, 833 + 1000 + 800);
+
+        assert_eq!(count(&db, "Event"), 2);
+        assert_eq!(count(&db, "Result"), 3);
+        assert_eq!(count(&db, "Runner"), 2);
+    }
+
+    #[test]
+    fn score_against_all_finishers() {
+        // The 2022 rules take the reference time from all finishers of the course
+        let db = database();
+        let event = add_event(&db, "forest-cup", 2022, "2022-01-15");
+        add_result(&db, event, "A", "Omega", "Lang", "H21", "01:00:00");
+        add_result(&db, event, "B", "Omega", "Lang", "H21", "00:50:00");
+        add_result(&db, event, "X", "Omega", "Lang", "H35", "00:40:00");
+
+        let what_if = WhatIf {
+            name: "A".to_owned(),
+            event_id: Some(event),
+            result: HypotheticalResult::Score(900),
+        };
+        let ranking = what_if_ranking(&db, "forest-cup", 2022, "H21", 3, &what_if).unwrap();
+        assert_eq!(find(&ranking, "A").scores[0].score, Some(900));
+        assert_eq!(find(&ranking, "B").scores[0].score, Some(800));
+    }
+}